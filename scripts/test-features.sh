@@ -0,0 +1,69 @@
+#!/usr/bin/env bash
+# Runs the tests under every feature that selects the public key type, and against every supported version of
+# `solana-program` 1.x and `anchor-lang`, by pinning them in the lockfile. The lockfile is restored afterwards.
+#
+# Usage: scripts/test-features.sh [extra cargo args, e.g. --offline]
+
+set -euo pipefail
+cd "$(dirname "$0")/.."
+
+# The minimum supported Rust version, i.e., the `rustc` of `cargo build-sbf` in Solana 1.16. Cargo of that version
+# cannot resolve the dev-dependencies, but without the Solana features the crate has no dependencies, so it is
+# compiled with `rustc` directly
+msrv=$(sed -n 's/^rust-version = "\(.*\)"$/\1/p' Cargo.toml)
+rustup toolchain install "$msrv" --profile minimal --no-self-update
+for features in "" "std" "std build"; do
+    echo "==> rustc +$msrv with features: ${features:-none}"
+    cfgs=()
+    for feature in $features; do
+        cfgs+=(--cfg "feature=\"$feature\"")
+    done
+    rustup run "$msrv" rustc --edition 2021 --crate-type lib --crate-name const_str_to_pubkey \
+        --out-dir target/msrv ${cfgs[@]+"${cfgs[@]}"} src/lib.rs
+done
+
+# Versions are pinned on top of a freshly resolved lockfile, so back up the developer's lockfile (if any) first
+if [ -f Cargo.lock ]; then
+    cp Cargo.lock Cargo.lock.bak
+    trap 'mv Cargo.lock.bak Cargo.lock; rm -f Cargo.lock.fresh' EXIT
+else
+    trap 'rm -f Cargo.lock Cargo.lock.fresh' EXIT
+fi
+cargo generate-lockfile "$@"
+cp Cargo.lock Cargo.lock.fresh
+
+run() {
+    echo "==> cargo test $*"
+    cargo test "$@"
+}
+
+# Replaces the locked version of `package` (matching `prefix`) with `version`.
+pin() {
+    local package=$1 prefix=$2 version=$3
+    shift 3
+    local locked
+    locked=$(grep -A1 "^name = \"$package\"$" Cargo.lock | grep -o "\"$prefix[0-9.]*\"" | tr -d '"')
+    cargo update "$@" -p "$package@$locked" --precise "$version"
+}
+
+run "$@"
+run "$@" --no-default-features
+run "$@" --no-default-features --features std
+run "$@" --no-default-features --features solana-pubkey
+run "$@" --no-default-features --features build
+
+for version in 1.16.27 1.17.26 1.18.26; do
+    cp Cargo.lock.fresh Cargo.lock
+    pin solana-program 1. "$version" "$@"
+    run "$@" --no-default-features --features solana-program-1
+done
+
+# `anchor-lang` 0.29 and 0.30 re-export `solana-program` 1.x, later versions re-export 2.x crates
+for versions in 0.29.0:1.16.27 0.30.1:1.18.26 0.31.1: 0.32.1:; do
+    cp Cargo.lock.fresh Cargo.lock
+    if [ -n "${versions#*:}" ]; then
+        pin solana-program 1. "${versions#*:}" "$@"
+    fi
+    pin anchor-lang 0. "${versions%:*}" "$@"
+    run "$@" --no-default-features --features anchor-lang
+done
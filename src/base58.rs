@@ -0,0 +1,492 @@
+//! Base58 decoding and encoding tables, a decoder for arbitrary fixed-size byte arrays, and a const public key
+//! encoder.
+
+use crate::{
+    bytes::subslice,
+    pubkey::{self, Pubkey},
+};
+
+/// Returns an array that represents a map from Base58 encoding character to number.
+///
+/// For example:
+/// ```
+/// use const_str_to_pubkey::get_base58ch_to_number_map;
+///
+/// let map = get_base58ch_to_number_map();
+/// assert!(map['1' as usize] == 0);
+/// assert!(map['2' as usize] == 1);
+/// assert!(map['A' as usize] == 9);
+/// assert!(map['B' as usize] == 10);
+/// assert!(map['a' as usize] == 33);
+/// // Invalid characters (like uppercase 'O') are mapped to 0xFF
+/// assert!(map['O' as usize] == 0xFF);
+/// ```
+pub const fn get_base58ch_to_number_map() -> [u8; 128] {
+    let mut map = [0xFF; 128];
+    let mut number = 0;
+
+    let mut i = '1' as usize;
+    while i <= '9' as usize {
+        map[i] = number;
+        number += 1;
+        i += 1;
+    }
+
+    i = 'A' as usize;
+    while i <= 'Z' as usize {
+        if i != 'I' as usize && i != 'O' as usize {
+            map[i] = number;
+            number += 1;
+        }
+        i += 1;
+    }
+
+    i = 'a' as usize;
+    while i <= 'z' as usize {
+        if i != 'l' as usize {
+            map[i] = number;
+            number += 1;
+        }
+        i += 1;
+    }
+
+    map
+}
+
+/// Returns an array that represents a map from number to Base58 encoding character.
+///
+/// This is the inverse of [`get_base58ch_to_number_map`]. For example:
+/// ```
+/// use const_str_to_pubkey::get_number_to_base58ch_map;
+///
+/// let map = get_number_to_base58ch_map();
+/// assert!(map[0] == b'1');
+/// assert!(map[9] == b'A');
+/// assert!(map[33] == b'a');
+/// assert!(map[57] == b'z');
+/// ```
+pub const fn get_number_to_base58ch_map() -> [u8; 58] {
+    let map = get_base58ch_to_number_map();
+    let mut inverse = [0u8; 58];
+
+    let mut i = 0;
+    while i < 128 {
+        if map[i] != 0xFF {
+            inverse[map[i] as usize] = i as u8;
+        }
+        i += 1;
+    }
+
+    inverse
+}
+
+/// A Base58 encoded public key, stored in a fixed-capacity buffer so that it can be built in const contexts.
+///
+/// Returned by [`pubkey_to_str`]. Use [`as_str`](ConstPubkeyStr::as_str) to borrow the string, or the
+/// [`pubkey_str!`](crate::pubkey_str) macro to get a `&'static str` directly.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstPubkeyStr {
+    buf: [u8; 44],
+    len: usize,
+}
+
+impl ConstPubkeyStr {
+    /// Returns the Base58 encoded public key.
+    pub const fn as_str(&self) -> &str {
+        match core::str::from_utf8(subslice(&self.buf, 0, self.len)) {
+            Ok(s) => s,
+            Err(_) => unreachable!(),
+        }
+    }
+}
+
+impl core::ops::Deref for ConstPubkeyStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for ConstPubkeyStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl core::fmt::Display for ConstPubkeyStr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl core::fmt::Debug for ConstPubkeyStr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Encodes 32 bytes with Base58, i.e., the string form of the public key with these bytes.
+///
+/// See [`pubkey_to_str`].
+pub const fn bytes_to_pubkey_str(bytes: &[u8; 32]) -> ConstPubkeyStr {
+    let map = get_number_to_base58ch_map();
+    // Base58 digits, least significant first
+    let mut digits = [0u8; 44];
+    let mut len = 0;
+
+    let mut i = 0;
+    while i < 32 {
+        let mut carry = bytes[i] as usize;
+
+        let mut j = 0;
+        while j < len {
+            carry += (digits[j] as usize) << 8;
+            digits[j] = (carry % 58) as u8;
+            carry /= 58;
+            j += 1;
+        }
+
+        while carry > 0 {
+            digits[len] = (carry % 58) as u8;
+            len += 1;
+            carry /= 58;
+        }
+
+        i += 1;
+    }
+
+    i = 0;
+    while i < 32 && bytes[i] == 0 {
+        digits[len] = 0;
+        len += 1;
+        i += 1;
+    }
+
+    let mut buf = [0u8; 44];
+    i = 0;
+    while i < len {
+        buf[i] = map[digits[len - 1 - i] as usize];
+        i += 1;
+    }
+
+    ConstPubkeyStr { buf, len }
+}
+
+/// Converts a [`Pubkey`](https://docs.rs/solana-program/latest/solana_program/pubkey/struct.Pubkey.html) to its
+/// Base58 string form at compile time.
+///
+/// This saves the compute units that `Pubkey::to_string` (or `msg!("{}", pubkey)`) would spend on-chain. For
+/// example:
+///
+/// ```
+/// use const_str_to_pubkey::{pubkey_to_str, str_to_pubkey, ConstPubkeyStr, Pubkey};
+///
+/// const ADMIN_PUBKEY: Pubkey = str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y");
+/// const ADMIN_PUBKEY_STR: ConstPubkeyStr = pubkey_to_str(&ADMIN_PUBKEY);
+/// assert_eq!(ADMIN_PUBKEY_STR.as_str(), "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y");
+/// ```
+pub const fn pubkey_to_str(pubkey: &Pubkey) -> ConstPubkeyStr {
+    bytes_to_pubkey_str(pubkey::as_bytes(pubkey))
+}
+
+/// Converts a constant [`Pubkey`](https://docs.rs/solana-program/latest/solana_program/pubkey/struct.Pubkey.html)
+/// to a `&'static str` at compile time.
+///
+/// The argument must be a constant expression. For example:
+///
+/// ```
+/// use const_str_to_pubkey::{pubkey_str, str_to_pubkey, Pubkey};
+///
+/// const ADMIN_PUBKEY: Pubkey = str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y");
+/// const ADMIN_PUBKEY_STR: &str = pubkey_str!(ADMIN_PUBKEY);
+/// assert_eq!(ADMIN_PUBKEY_STR, "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y");
+/// ```
+#[macro_export]
+macro_rules! pubkey_str {
+    ($pubkey:expr) => {{
+        const PUBKEY_STR: &str = {
+            const ENCODED: $crate::ConstPubkeyStr = $crate::pubkey_to_str(&$pubkey);
+            ENCODED.as_str()
+        };
+        PUBKEY_STR
+    }};
+}
+
+/// The error returned by [`try_decode_base58`] (and [`try_str_to_pubkey`](crate::try_str_to_pubkey), etc.) when a
+/// string is not a valid Base58 encoding of the expected number of bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The string is empty.
+    Empty,
+    /// The string is longer than [`max_encoded_len`] of the expected number of bytes.
+    TooLong,
+    /// The string contains a character that is not in the Base58 alphabet.
+    InvalidCharacter {
+        /// Byte index of the offending character in the string.
+        index: usize,
+        /// The offending byte.
+        character: u8,
+    },
+    /// The decoded value does not fit in the expected number of bytes.
+    TooLarge,
+    /// The string decodes to fewer bytes than expected.
+    TooShort,
+}
+
+impl DecodeError {
+    /// Returns a static description of the error.
+    ///
+    /// This is the message [`decode_base58`] and [`str_to_pubkey`](crate::str_to_pubkey) panic with, so it can be
+    /// used in const contexts.
+    pub const fn message(&self) -> &'static str {
+        match self {
+            DecodeError::Empty => "Base58 string cannot be empty",
+            DecodeError::TooLong => "Base58 string is too long for the expected number of bytes",
+            DecodeError::InvalidCharacter { .. } => "Invalid Base58 character found",
+            DecodeError::TooLarge => "Base58 string decodes to more bytes than expected",
+            DecodeError::TooShort => "Base58 string decodes to fewer bytes than expected",
+        }
+    }
+}
+
+impl core::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DecodeError::InvalidCharacter { index, character } => write!(
+                f,
+                "{} (byte {:#04x} at index {})",
+                self.message(),
+                character,
+                index
+            ),
+            _ => f.write_str(self.message()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {}
+
+/// Returns the maximum length of the Base58 encoding of `n` bytes, i.e., `ceil(n * log(256) / log(58))`.
+///
+/// For example:
+/// ```
+/// use const_str_to_pubkey::max_encoded_len;
+///
+/// assert_eq!(max_encoded_len(32), 44);
+/// assert_eq!(max_encoded_len(64), 88);
+/// ```
+pub const fn max_encoded_len(n: usize) -> usize {
+    // log(256) / log(58) = 1.36565823...
+    (n * 1_365_659 + 999_999) / 1_000_000
+}
+
+/// Decodes a Base58 string to exactly `N` bytes, returning an error instead of panicking when the string is invalid.
+///
+/// The string may be at most [`max_encoded_len`]`(N)` characters long, and must decode to exactly `N` bytes
+/// (counting the leading zero bytes encoded as leading '1' characters), which matches `bs58` and the `FromStr`
+/// implementations of Solana types. For example:
+///
+/// ```
+/// use const_str_to_pubkey::{try_decode_base58, DecodeError};
+///
+/// const BYTES: Result<[u8; 4], DecodeError> = try_decode_base58("1Ldp");
+/// assert_eq!(BYTES, Ok([0, 1, 2, 3]));
+/// assert_eq!(try_decode_base58::<4>("Ldp"), Err(DecodeError::TooShort));
+/// ```
+pub const fn try_decode_base58<const N: usize>(s: &str) -> Result<[u8; N], DecodeError> {
+    try_decode_base58_bytes(s.as_bytes())
+}
+
+/// Same as [`try_decode_base58`], but takes the string as bytes.
+pub(crate) const fn try_decode_base58_bytes<const N: usize>(
+    s: &[u8],
+) -> Result<[u8; N], DecodeError> {
+    if s.is_empty() {
+        return Err(DecodeError::Empty);
+    }
+    if s.len() > max_encoded_len(N) {
+        return Err(DecodeError::TooLong);
+    }
+
+    let map = get_base58ch_to_number_map();
+    // Decoded bytes, least significant first
+    let mut bytes = [0u8; N];
+    let mut i = 0;
+    let mut index = 0;
+
+    while i < s.len() {
+        let invalid_character = DecodeError::InvalidCharacter {
+            index: i,
+            character: s[i],
+        };
+        if s[i] > 127 {
+            return Err(invalid_character);
+        }
+
+        let mut val = map[s[i] as usize] as usize;
+        if val == 0xFF {
+            return Err(invalid_character);
+        }
+
+        let mut j = 0;
+        while j < index {
+            val += (bytes[j] as usize) * 58;
+            bytes[j] = (val & 0xFF) as u8;
+            val >>= 8;
+            j += 1;
+        }
+
+        while val > 0 {
+            if index == N {
+                return Err(DecodeError::TooLarge);
+            }
+            bytes[index] = (val & 0xFF) as u8;
+            index += 1;
+            val >>= 8;
+        }
+
+        i += 1;
+    }
+
+    i = 0;
+    while i < s.len() && s[i] == b'1' {
+        if index == N {
+            return Err(DecodeError::TooLarge);
+        }
+        bytes[index] = 0;
+        index += 1;
+        i += 1;
+    }
+
+    if index < N {
+        return Err(DecodeError::TooShort);
+    }
+
+    i = 0;
+    while i < N / 2 {
+        (bytes[i], bytes[N - 1 - i]) = (bytes[N - 1 - i], bytes[i]);
+        i += 1;
+    }
+
+    Ok(bytes)
+}
+
+/// Decodes a Base58 string to exactly `N` bytes.
+///
+/// This is useful for embedding other Base58 encoded values, such as 64-byte signatures or secret keys, from
+/// constant strings. For example:
+///
+/// ```ignore
+/// use const_str_to_pubkey::decode_base58;
+/// const SECRET_KEY: [u8; 64] = decode_base58(env!("SECRET_KEY"));
+/// ```
+///
+/// # Panics
+///
+/// Panics with [`DecodeError::message`] if the string is not a valid encoding of `N` bytes (see
+/// [`try_decode_base58`]). In a const context, this is a compile error.
+pub const fn decode_base58<const N: usize>(s: &str) -> [u8; N] {
+    match try_decode_base58(s) {
+        Ok(bytes) => bytes,
+        Err(err) => panic!("{}", err.message()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::try_str_to_pubkey;
+    use solana_program::pubkey::Pubkey as SolanaPubkey;
+
+    const MAP: [u8; 128] = get_base58ch_to_number_map();
+
+    #[test]
+    fn test_base58ch_to_number_map() {
+        assert_eq!(MAP['1' as usize], 0);
+        assert_eq!(MAP['2' as usize], 1);
+        assert_eq!(MAP['A' as usize], 9);
+        assert_eq!(MAP['a' as usize], 33);
+
+        // Invalid characters are mapped to 0xFF
+        assert_eq!(MAP['0' as usize], 0xFF);
+        assert_eq!(MAP['I' as usize], 0xFF);
+        assert_eq!(MAP['O' as usize], 0xFF);
+        assert_eq!(MAP['l' as usize], 0xFF);
+        assert_eq!(MAP['+' as usize], 0xFF);
+
+        let inverse = get_number_to_base58ch_map();
+        for (number, ch) in inverse.iter().enumerate() {
+            assert_eq!(MAP[*ch as usize] as usize, number);
+        }
+    }
+
+    #[test]
+    fn test_decode_base58() {
+        const MAX_64: &str = "67rpwLCuS5DGA8KGZXKsVQ7dnPb9goRLoKfgGbLfQg9WoLUgNY77E2jT11fem3coV9nAkguBACzrU1iyZM4B8roQ";
+        const BYTES_64: [u8; 64] = decode_base58(MAX_64);
+        assert_eq!(BYTES_64, [0xFF; 64]);
+
+        let mut expected = [0u8; 64];
+        for (i, byte) in expected.iter_mut().enumerate().skip(2) {
+            *byte = i as u8 - 1;
+        }
+        assert_eq!(
+            try_decode_base58::<64>(
+                "114UoxkBcexSgjZdTDX2sxJDMwhXMgs4ZREBTR5bBjJdB1oJd7oMJq4WdjTADqL3e97GKwtfw6eYsG6FYMChhw"
+            ),
+            Ok(expected)
+        );
+
+        assert_eq!(
+            try_decode_base58::<64>(
+                "67rpwLCuS5DGA8KGZXKsVQ7dnPb9goRLoKfgGbLfQg9WoLUgNY77E2jT11fem3coV9nAkguBACzrU1iyZM4B8roR"
+            ),
+            Err(DecodeError::TooLarge)
+        );
+        assert_eq!(
+            try_decode_base58::<64>(&format!("{MAX_64}1")),
+            Err(DecodeError::TooLong)
+        );
+        assert_eq!(try_decode_base58::<64>(""), Err(DecodeError::Empty));
+        assert_eq!(try_decode_base58::<4>("1Ldp"), Ok([0, 1, 2, 3]));
+        assert_eq!(try_decode_base58::<4>("11Ldp"), Err(DecodeError::TooLarge));
+        assert_eq!(try_decode_base58::<1>("1"), Ok([0]));
+    }
+
+    #[test]
+    fn test_pubkey_to_str() {
+        const PUBKEY_STR: &str = "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y";
+        const PUBKEY: Pubkey = crate::str_to_pubkey(PUBKEY_STR);
+        const PUBKEY_STR_AGAIN: &str = pubkey_str!(PUBKEY);
+        assert_eq!(PUBKEY_STR_AGAIN, PUBKEY_STR);
+
+        for bytes in [[0u8; 32], [0xFF; 32], *pubkey::as_bytes(&PUBKEY)] {
+            let encoded = bytes_to_pubkey_str(&bytes);
+            assert_eq!(
+                encoded.as_str(),
+                SolanaPubkey::new_from_array(bytes).to_string()
+            );
+            assert_eq!(try_str_to_pubkey(&encoded), Ok(pubkey::from_bytes(bytes)));
+        }
+
+        // Leading zero bytes are encoded as leading '1' characters
+        for zeros in 0..=32 {
+            let mut bytes = [0x5A; 32];
+            bytes[..zeros].fill(0);
+            assert_eq!(
+                pubkey_to_str(&pubkey::from_bytes(bytes)).as_str(),
+                SolanaPubkey::new_from_array(bytes).to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_max_encoded_len() {
+        assert_eq!(max_encoded_len(0), 0);
+        assert_eq!(max_encoded_len(1), 2);
+        assert_eq!(max_encoded_len(32), 44);
+        assert_eq!(max_encoded_len(64), 88);
+    }
+}
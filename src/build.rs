@@ -0,0 +1,473 @@
+//! Validating public keys in a build script, so that a bad key fails the build with a message naming it, instead of
+//! failing const evaluation with a generic one.
+//!
+//! Add the crate as a build dependency with the `build` feature:
+//!
+//! ```toml
+//! [build-dependencies]
+//! const_str_to_pubkey = { version = "0.2", default-features = false, features = ["build"] }
+//! ```
+//!
+//! Then, in `build.rs`, validate the environment variables the crate reads with `env!`, `env_pubkey!`, etc.,
+//! e.g., the ones set in `.cargo/config.toml`, and/or pass on the keys of a config file that
+//! [`config_pubkey!`](crate::config_pubkey!) could read:
+//!
+//! ```no_run
+//! use const_str_to_pubkey::build::{emit_config_pubkeys, emit_env_pubkeys};
+//!
+//! let result = emit_env_pubkeys(&["ADMIN_PUBKEY", "ORACLE_PUBKEY"])
+//!     .and_then(|()| emit_config_pubkeys("addresses.toml"));
+//! if let Err(err) = result {
+//!     panic!("{err}");
+//! }
+//! ```
+
+use std::{
+    borrow::ToOwned,
+    collections::HashSet,
+    fmt,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    string::String,
+    vec::Vec,
+};
+
+use crate::{
+    config::{config_start, line_of, next_entry, range},
+    try_str_to_pubkey, ConfigError, DecodePubkeyError, Pubkey,
+};
+
+/// The error returned by the functions of [`build`](self).
+#[derive(Debug)]
+pub enum BuildError {
+    /// The environment variable is not set.
+    MissingEnv {
+        /// Name of the environment variable.
+        name: String,
+    },
+    /// The environment variable is not a valid public key.
+    InvalidEnv {
+        /// Name of the environment variable.
+        name: String,
+        /// Value of the environment variable.
+        value: String,
+        /// Why the value is not a valid public key.
+        error: DecodePubkeyError,
+    },
+    /// The config file is not valid syntax.
+    InvalidConfig {
+        /// Path of the config file.
+        path: PathBuf,
+        /// Line number, starting from 1.
+        line: usize,
+        /// What is wrong with the config.
+        error: ConfigError,
+    },
+    /// A key is found more than once in the config file.
+    DuplicateConfigKey {
+        /// Path of the config file.
+        path: PathBuf,
+        /// Line number of the second occurrence, starting from 1.
+        line: usize,
+        /// Name of the key.
+        name: String,
+    },
+    /// An entry of the config file is not a valid public key.
+    InvalidConfigEntry {
+        /// Path of the config file.
+        path: PathBuf,
+        /// Line number, starting from 1.
+        line: usize,
+        /// Name of the entry.
+        name: String,
+        /// Value of the entry.
+        value: String,
+        /// Why the value is not a valid public key.
+        error: DecodePubkeyError,
+    },
+    /// The config file, or the standard output, could not be read or written.
+    Io {
+        /// Path of the config file, or `None` for the standard output.
+        path: Option<PathBuf>,
+        /// The underlying error.
+        error: io::Error,
+    },
+}
+
+/// Describes why `value` is not a valid public key, showing the offending character if there is one.
+fn describe(f: &mut fmt::Formatter<'_>, error: &DecodePubkeyError, value: &str) -> fmt::Result {
+    match *error {
+        DecodePubkeyError::InvalidCharacter { index, character }
+            if character.is_ascii_graphic() =>
+        {
+            write!(
+                f,
+                "Invalid Base58 character '{}' at index {} of {:?}",
+                character as char, index, value
+            )
+        }
+        DecodePubkeyError::InvalidCharacter { index, character } => write!(
+            f,
+            "Invalid Base58 character (byte {:#04x}) at index {} of {:?}",
+            character, index, value
+        ),
+        _ => write!(f, "{} ({:?})", error.message(), value),
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MissingEnv { name } => {
+                write!(f, "Environment variable `{}` is not set", name)
+            }
+            BuildError::InvalidEnv { name, value, error } => {
+                write!(f, "Invalid public key in environment variable `{}`: ", name)?;
+                describe(f, error, value)
+            }
+            BuildError::InvalidConfig { path, line, error } => {
+                write!(f, "{} at {}:{}", error.message(), path.display(), line)
+            }
+            BuildError::DuplicateConfigKey { path, line, name } => write!(
+                f,
+                "Key `{}` found more than once in config at {}:{}",
+                name,
+                path.display(),
+                line
+            ),
+            BuildError::InvalidConfigEntry {
+                path,
+                line,
+                name,
+                value,
+                error,
+            } => {
+                write!(
+                    f,
+                    "Invalid public key for `{}` at {}:{}: ",
+                    name,
+                    path.display(),
+                    line
+                )?;
+                describe(f, error, value)
+            }
+            BuildError::Io {
+                path: Some(path),
+                error,
+            } => write!(f, "Cannot read {}: {}", path.display(), error),
+            BuildError::Io { path: None, error } => {
+                write!(f, "Cannot write build script output: {}", error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::InvalidEnv { error, .. } | BuildError::InvalidConfigEntry { error, .. } => {
+                Some(error)
+            }
+            BuildError::Io { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+fn stdout_error(error: io::Error) -> BuildError {
+    BuildError::Io { path: None, error }
+}
+
+fn env_pubkey_to(out: &mut impl Write, name: &str) -> Result<(String, Pubkey), BuildError> {
+    writeln!(out, "cargo:rerun-if-env-changed={}", name).map_err(stdout_error)?;
+    let value = match std::env::var_os(name) {
+        Some(value) => value.to_string_lossy().into_owned(),
+        None => {
+            return Err(BuildError::MissingEnv {
+                name: name.to_owned(),
+            })
+        }
+    };
+    match try_str_to_pubkey(&value) {
+        Ok(pubkey) => Ok((value, pubkey)),
+        Err(error) => Err(BuildError::InvalidEnv {
+            name: name.to_owned(),
+            value,
+            error,
+        }),
+    }
+}
+
+/// Reads and validates the public key in an environment variable, telling cargo to rerun the build script when the
+/// variable changes.
+pub fn env_pubkey(name: &str) -> Result<Pubkey, BuildError> {
+    env_pubkey_to(&mut io::stdout().lock(), name).map(|(_, pubkey)| pubkey)
+}
+
+fn emit_env_pubkeys_to(out: &mut impl Write, names: &[&str]) -> Result<(), BuildError> {
+    for name in names {
+        let (value, _) = env_pubkey_to(out, name)?;
+        writeln!(out, "cargo:rustc-env={}={}", name, value).map_err(stdout_error)?;
+    }
+    Ok(())
+}
+
+/// Validates the public key in each environment variable, and passes it on to the crate being built, telling cargo
+/// to rerun the build script when any of them changes.
+///
+/// Stops at the first variable that is not set or not a valid public key.
+pub fn emit_env_pubkeys(names: &[&str]) -> Result<(), BuildError> {
+    emit_env_pubkeys_to(&mut io::stdout().lock(), names)
+}
+
+/// Parses a config file of `NAME = "base58"` lines or a flat JSON object, returning each name and value.
+///
+/// The syntax is the same as [`config_pubkey`](crate::config_pubkey()), so every file accepted here can be read by
+/// `config_pubkey!`. Unlike `config_pubkey!`, which only checks the key it reads, every value is checked to be a
+/// valid public key and every key to be unique. `path` is only used in errors.
+pub fn parse_config_pubkeys(
+    s: &str,
+    path: impl AsRef<Path>,
+) -> Result<Vec<(String, String)>, BuildError> {
+    let bytes = s.as_bytes();
+    let path = path.as_ref();
+    let to_string = |entry_range| String::from_utf8_lossy(range(bytes, entry_range)).into_owned();
+
+    // Check the syntax of the whole config first, like `config_pubkey!`
+    let mut parsed = Vec::new();
+    let mut cursor = config_start(bytes);
+    loop {
+        match next_entry(bytes, cursor) {
+            Ok(Some((entry, next))) => {
+                parsed.push(entry);
+                cursor = next;
+            }
+            Ok(None) => break,
+            Err(error) => {
+                let index = match error {
+                    ConfigError::InvalidSyntax { index } => index,
+                    _ => unreachable!("only syntax errors come from parsing"),
+                };
+                return Err(BuildError::InvalidConfig {
+                    path: path.to_owned(),
+                    line: line_of(bytes, index),
+                    error,
+                });
+            }
+        }
+    }
+
+    let mut names = HashSet::new();
+    let mut entries = Vec::with_capacity(parsed.len());
+    for entry in parsed {
+        let (name, value) = (to_string(entry.key), to_string(entry.value));
+        if let Err(error) = try_str_to_pubkey(&value) {
+            return Err(BuildError::InvalidConfigEntry {
+                path: path.to_owned(),
+                line: line_of(bytes, entry.value.0),
+                name,
+                value,
+                error,
+            });
+        }
+        if !names.insert(range(bytes, entry.key)) {
+            return Err(BuildError::DuplicateConfigKey {
+                path: path.to_owned(),
+                line: line_of(bytes, entry.key.0),
+                name,
+            });
+        }
+        entries.push((name, value));
+    }
+    Ok(entries)
+}
+
+fn emit_config_pubkeys_to(out: &mut impl Write, path: &Path) -> Result<(), BuildError> {
+    writeln!(out, "cargo:rerun-if-changed={}", path.display()).map_err(stdout_error)?;
+    let s = std::fs::read_to_string(path).map_err(|error| BuildError::Io {
+        path: Some(path.to_owned()),
+        error,
+    })?;
+    for (name, value) in parse_config_pubkeys(&s, path)? {
+        writeln!(out, "cargo:rustc-env={}={}", name, value).map_err(stdout_error)?;
+    }
+    Ok(())
+}
+
+/// Reads a config file of `NAME = "base58"` lines or a flat JSON object (see [`parse_config_pubkeys`]), and passes
+/// each public key on to the crate being built as an environment variable, telling cargo to rerun the build script
+/// when the file changes.
+///
+/// A relative path is relative to the directory of the build script's package.
+pub fn emit_config_pubkeys(path: impl AsRef<Path>) -> Result<(), BuildError> {
+    emit_config_pubkeys_to(&mut io::stdout().lock(), path.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::str_to_pubkey;
+
+    const KEY: &str = "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y";
+
+    #[test]
+    fn test_emit_env_pubkeys() {
+        std::env::set_var("CONST_STR_TO_PUBKEY_BUILD_VALID", KEY);
+        std::env::set_var(
+            "CONST_STR_TO_PUBKEY_BUILD_INVALID",
+            "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ0rGB4Y",
+        );
+
+        let mut out = Vec::new();
+        emit_env_pubkeys_to(&mut out, &["CONST_STR_TO_PUBKEY_BUILD_VALID"]).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "cargo:rerun-if-env-changed=CONST_STR_TO_PUBKEY_BUILD_VALID\n\
+                 cargo:rustc-env=CONST_STR_TO_PUBKEY_BUILD_VALID={KEY}\n"
+            )
+        );
+        assert_eq!(
+            env_pubkey("CONST_STR_TO_PUBKEY_BUILD_VALID").unwrap(),
+            str_to_pubkey(KEY)
+        );
+
+        let err = emit_env_pubkeys_to(
+            &mut Vec::new(),
+            &[
+                "CONST_STR_TO_PUBKEY_BUILD_VALID",
+                "CONST_STR_TO_PUBKEY_BUILD_INVALID",
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid public key in environment variable `CONST_STR_TO_PUBKEY_BUILD_INVALID`: Invalid Base58 \
+             character '0' at index 38 of \"CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ0rGB4Y\""
+        );
+
+        let err = env_pubkey("CONST_STR_TO_PUBKEY_UNSET").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Environment variable `CONST_STR_TO_PUBKEY_UNSET` is not set"
+        );
+    }
+
+    #[test]
+    fn test_parse_config_pubkeys() {
+        let config = format!(
+            "# Addresses\n\nADMIN = \"{KEY}\" # Admin\n  \"SYSTEM\"=\"11111111111111111111111111111111\"\n"
+        );
+        assert_eq!(
+            parse_config_pubkeys(&config, "addresses.toml").unwrap(),
+            [
+                ("ADMIN".to_owned(), KEY.to_owned()),
+                (
+                    "SYSTEM".to_owned(),
+                    "11111111111111111111111111111111".to_owned()
+                ),
+            ]
+        );
+        let json = format!("{{\"ADMIN\": \"{KEY}\"}}");
+        assert_eq!(
+            parse_config_pubkeys(&json, "addresses.json").unwrap(),
+            [("ADMIN".to_owned(), KEY.to_owned())]
+        );
+
+        let err = parse_config_pubkeys(
+            &format!("ADMIN = \"{KEY}\"\nMINT = \"2\"\n"),
+            "addresses.toml",
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid public key for `MINT` at addresses.toml:2: Base58 string decodes to fewer bytes than expected \
+             (\"2\")"
+        );
+        let err =
+            parse_config_pubkeys(&format!("\nADMIN = {KEY}\n"), "addresses.toml").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Config must be `key = \"value\"` lines or a flat JSON object of strings at addresses.toml:2"
+        );
+        let err = parse_config_pubkeys(
+            &format!("ADMIN = \"{KEY}\"\nADMIN = \"{KEY}\""),
+            "addresses.toml",
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Key `ADMIN` found more than once in config at addresses.toml:2"
+        );
+    }
+
+    #[test]
+    fn test_parse_large_config() {
+        let config = include_str!("../testdata/addresses.toml");
+        let entries = parse_config_pubkeys(config, "addresses.toml").unwrap();
+        assert_eq!(entries.len(), 300);
+        assert_eq!(entries[299].0, "key_299");
+    }
+
+    #[test]
+    fn test_same_configs_as_config_pubkey() {
+        // Every fixture accepted by `build` is read the same way by `config_pubkey!`, and every syntax error is
+        // rejected by both. `config_pubkey!` only decodes and checks the key it reads, so it accepts bad other
+        // entries that `build` rejects.
+        let fixtures = [
+            format!("ADMIN = \"{KEY}\" # Admin\r\n# Comment\n\n\"MINT\" = \"{KEY}\""),
+            format!("\n{{\n  \"ADMIN\": \"{KEY}\",\n  \"MINT\" : \"{KEY}\"\n}}\n"),
+            format!("ADMIN={KEY}"),
+            format!("ADMIN = \"{KEY}\"\nMINT = \"{KEY}\" MINT"),
+            format!("ADMIN = \"{KEY}\"\nMINT = \"2\""),
+            format!("ADMIN = \"{KEY}\"\nMINT = \"{KEY}\"\nMINT = \"{KEY}\""),
+            format!("ADMIN = \"{KEY}\"\nADMIN = \"{KEY}\""),
+            format!("\"ADMIN KEY\" = \"{KEY}\""),
+            format!("[addresses]\nADMIN = \"{KEY}\""),
+            format!("{{\"ADMIN\": \"{KEY}\",}}"),
+        ];
+        for fixture in &fixtures {
+            let built = parse_config_pubkeys(fixture, "addresses.toml");
+            let read = crate::try_config_pubkey(fixture, "ADMIN");
+            match built {
+                Ok(entries) => {
+                    assert_eq!(
+                        read,
+                        Ok(try_str_to_pubkey(&entries[0].1).unwrap()),
+                        "{fixture:?}"
+                    )
+                }
+                Err(BuildError::InvalidConfig { .. }) => {
+                    assert!(
+                        matches!(read, Err(ConfigError::InvalidSyntax { .. })),
+                        "{fixture:?}"
+                    )
+                }
+                Err(_) => {}
+            }
+        }
+        assert!(crate::try_config_pubkey(&fixtures[4], "ADMIN").is_ok());
+        assert!(parse_config_pubkeys(&fixtures[4], "addresses.toml").is_err());
+        assert!(parse_config_pubkeys(&fixtures[5], "addresses.toml").is_err());
+    }
+
+    #[test]
+    fn test_emit_config_pubkeys() {
+        let path = std::env::temp_dir().join("const_str_to_pubkey_build_test.toml");
+        std::fs::write(&path, format!("ADMIN = \"{KEY}\"\n")).unwrap();
+
+        let mut out = Vec::new();
+        emit_config_pubkeys_to(&mut out, &path).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "cargo:rerun-if-changed={}\ncargo:rustc-env=ADMIN={KEY}\n",
+                path.display()
+            )
+        );
+
+        std::fs::remove_file(&path).unwrap();
+        let err = emit_config_pubkeys_to(&mut Vec::new(), &path).unwrap_err();
+        assert!(matches!(err, BuildError::Io { path: Some(_), .. }));
+    }
+}
@@ -0,0 +1,35 @@
+//! Byte string helpers shared by the const parsers and encoders.
+
+/// Returns the index of the first byte at or after `i` that is not ASCII whitespace (space, tab, CR or LF).
+pub(crate) const fn skip_whitespace(s: &[u8], mut i: usize) -> usize {
+    while i < s.len() && matches!(s[i], b' ' | b'\t' | b'\n' | b'\r') {
+        i += 1;
+    }
+    i
+}
+
+/// Returns whether `a` and `b` are equal, i.e., `a == b` in const contexts.
+pub(crate) const fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Returns `&s[begin..end]` in const contexts, where slicing and `split_at` are not usable on older compilers.
+///
+/// # Panics
+///
+/// Panics if `begin > end` or `end > s.len()`.
+pub(crate) const fn subslice(s: &[u8], begin: usize, end: usize) -> &[u8] {
+    assert!(begin <= end && end <= s.len());
+    // SAFETY: `begin..end` is within `s`
+    unsafe { core::slice::from_raw_parts(s.as_ptr().add(begin), end - begin) }
+}
@@ -0,0 +1,265 @@
+//! Sets of public keys that differ between clusters (e.g., localnet, devnet and mainnet), one of which is selected at
+//! compile time. See [`cluster_profiles!`](crate::cluster_profiles).
+
+use crate::{bytes::bytes_eq, message::ConstMessage, pubkey::Pubkey, try_str_to_pubkey};
+
+const fn find_profile(names: &[&str], name: &str) -> Option<usize> {
+    let mut i = 0;
+    while i < names.len() {
+        if bytes_eq(names[i].as_bytes(), name.as_bytes()) {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Called by [`cluster_profiles!`](crate::cluster_profiles) to decode a field of a profile, naming the field if the
+/// string is not a valid public key.
+pub const fn profile_pubkey(s: &str, profile: &'static str, field: &'static str) -> Pubkey {
+    match try_str_to_pubkey(s) {
+        Ok(pubkey) => pubkey,
+        Err(err) => {
+            let message = ConstMessage::<256>::new()
+                .push_str("Invalid public key for `")
+                .push_str(profile)
+                .push_str(".")
+                .push_str(field)
+                .push_str("`: ")
+                .push_str(err.message());
+            panic!("{}", message.as_str());
+        }
+    }
+}
+
+/// Called by [`cluster_profiles!`](crate::cluster_profiles) to select a profile: the one whose feature is enabled,
+/// or else the one named by the environment variable, or else the default one.
+pub const fn select_profile(
+    names: &[&str],
+    features: &[bool],
+    env_name: &'static str,
+    env: Option<&str>,
+    default: &'static str,
+) -> usize {
+    let mut selected = None;
+    let mut i = 0;
+    while i < features.len() {
+        if features[i] {
+            if let Some(first) = selected {
+                let message = ConstMessage::<256>::new()
+                    .push_str("Only one cluster feature can be enabled, found `")
+                    .push_str(names[first])
+                    .push_str("` and `")
+                    .push_str(names[i])
+                    .push_str("`");
+                panic!("{}", message.as_str());
+            }
+            selected = Some(i);
+        }
+        i += 1;
+    }
+    if let Some(index) = selected {
+        return index;
+    }
+
+    let (name, source) = match env {
+        Some(name) => (name, env_name),
+        None => (default, "the default"),
+    };
+    match find_profile(names, name) {
+        Some(index) => index,
+        None => {
+            let message = ConstMessage::<256>::new()
+                .push_str("Unknown cluster `")
+                .push_str(name)
+                .push_str("` in ")
+                .push_str(source);
+            panic!("{}", message.as_str());
+        }
+    }
+}
+
+/// Declares a struct of public keys, one constant of it per cluster, and selects one of them at compile time.
+///
+/// Each profile is given as strings, decoded like [`str_to_pubkey`](crate::str_to_pubkey). Every profile is
+/// checked, whether it is selected or not: compilation fails if a profile is missing a field, has an unknown field,
+/// or has an invalid public key (naming the profile and the field).
+///
+/// `CURRENT` is the selected profile, and `CLUSTER` its name. The profile is selected by, in order:
+///
+/// 1. the cargo feature of the calling crate with the profile's name, e.g., `--features devnet`,
+/// 2. the environment variable given by `env`, e.g., `SOLANA_CLUSTER=devnet`,
+/// 3. the profile given by `default`.
+///
+/// For example:
+///
+/// ```
+/// use const_str_to_pubkey::{cluster_profiles, str_to_pubkey};
+///
+/// cluster_profiles! {
+///     /// Addresses that differ between clusters.
+///     pub struct Addresses {
+///         pub admin,
+///         pub mint,
+///     }
+///
+///     env = "CONST_STR_TO_PUBKEY_DOC_CLUSTER", default = "localnet";
+///
+///     LOCALNET = "localnet" {
+///         admin: "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y",
+///         mint: "So11111111111111111111111111111111111111112",
+///     }
+///     MAINNET = "mainnet" {
+///         admin: "9TfziDGboySLJTXXfip4kQWfPYkGBhnsZanvJfSTAzjk",
+///         mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+///     }
+/// }
+///
+/// const ADMIN: const_str_to_pubkey::Pubkey = Addresses::CURRENT.admin;
+/// assert_eq!(Addresses::CLUSTER, "localnet");
+/// assert_eq!(ADMIN, str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y"));
+/// ```
+#[macro_export]
+macro_rules! cluster_profiles {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $($(#[$field_attr:meta])* $field_vis:vis $field:ident),* $(,)?
+        }
+
+        env = $env:literal, default = $default:literal;
+
+        $($profile:ident = $cluster:literal {
+            $($profile_field:ident: $value:expr),* $(,)?
+        })+
+    ) => {
+        $(#[$attr])*
+        $vis struct $name {
+            $($(#[$field_attr])* $field_vis $field: $crate::__private::Pubkey,)*
+        }
+
+        impl $name {
+            $(
+                #[doc = concat!("The `", $cluster, "` profile.")]
+                pub const $profile: Self = Self {
+                    $($profile_field: $crate::__private::profile_pubkey(
+                        $value,
+                        stringify!($profile),
+                        stringify!($profile_field),
+                    ),)*
+                };
+            )+
+
+            const PROFILES: &'static [&'static Self] = &[$(&Self::$profile),+];
+
+            // The features are only declared by crates that select the profile with them
+            #[allow(unexpected_cfgs)]
+            const INDEX: usize = $crate::__private::select_profile(
+                &[$($cluster),+],
+                &[$(cfg!(feature = $cluster)),+],
+                $env,
+                option_env!($env),
+                $default,
+            );
+
+            /// The name of the selected profile.
+            pub const CLUSTER: &'static str = [$($cluster),+][Self::INDEX];
+
+            /// The selected profile.
+            pub const CURRENT: Self = Self {
+                $($field: Self::PROFILES[Self::INDEX].$field,)*
+            };
+        }
+
+        // Check every profile, not only the selected one
+        const _: () = {
+            $(let _ = $name::$profile;)+
+        };
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::str_to_pubkey;
+
+    crate::cluster_profiles! {
+        struct Addresses {
+            admin,
+            mint,
+        }
+
+        env = "CONST_STR_TO_PUBKEY_UNSET", default = "devnet";
+
+        LOCALNET = "localnet" {
+            admin: "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y",
+            mint: "So11111111111111111111111111111111111111112",
+        }
+        DEVNET = "devnet" {
+            mint: "4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU",
+            admin: "9TfziDGboySLJTXXfip4kQWfPYkGBhnsZanvJfSTAzjk",
+        }
+    }
+
+    #[test]
+    fn test_cluster_profiles() {
+        assert_eq!(Addresses::CLUSTER, "devnet");
+        assert_eq!(
+            Addresses::CURRENT.admin,
+            str_to_pubkey("9TfziDGboySLJTXXfip4kQWfPYkGBhnsZanvJfSTAzjk")
+        );
+        assert_eq!(Addresses::CURRENT.mint, Addresses::DEVNET.mint);
+        assert_eq!(
+            Addresses::LOCALNET.mint,
+            str_to_pubkey("So11111111111111111111111111111111111111112")
+        );
+    }
+
+    #[test]
+    fn test_select_profile() {
+        const NAMES: &[&str] = &["localnet", "devnet", "mainnet"];
+        let select =
+            |features: [bool; 3], env| select_profile(NAMES, &features, "CLUSTER", env, "localnet");
+        assert_eq!(select([false; 3], None), 0);
+        assert_eq!(select([false; 3], Some("mainnet")), 2);
+        assert_eq!(select([false, true, false], Some("mainnet")), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Unknown cluster `testnet` in CLUSTER")]
+    fn test_unknown_cluster() {
+        select_profile(
+            &["localnet", "devnet"],
+            &[false; 2],
+            "CLUSTER",
+            Some("testnet"),
+            "localnet",
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Only one cluster feature can be enabled, found `localnet` and `devnet`"
+    )]
+    fn test_several_cluster_features() {
+        select_profile(
+            &["localnet", "devnet"],
+            &[true; 2],
+            "CLUSTER",
+            None,
+            "localnet",
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid public key for `DEVNET.admin`: Invalid Base58 character found"
+    )]
+    fn test_invalid_profile_pubkey() {
+        profile_pubkey(
+            "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ0rGB4Y",
+            "DEVNET",
+            "admin",
+        );
+    }
+}
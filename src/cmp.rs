@@ -0,0 +1,202 @@
+//! Comparisons of public keys in const contexts, where `PartialEq` and `Ord` are not available.
+
+use crate::{
+    message::ConstMessage,
+    pubkey::{self, Pubkey},
+    pubkey_to_str,
+};
+use core::cmp::Ordering;
+
+/// Returns whether two public keys are equal, like `==` but usable in const contexts.
+///
+/// For example:
+///
+/// ```
+/// use const_str_to_pubkey::{pubkey_eq, str_to_pubkey, Pubkey};
+///
+/// const ADMIN: Pubkey = str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y");
+/// const SYSTEM_PROGRAM: Pubkey = str_to_pubkey("11111111111111111111111111111111");
+/// const _: () = assert!(!pubkey_eq(&ADMIN, &SYSTEM_PROGRAM));
+/// ```
+pub const fn pubkey_eq(a: &Pubkey, b: &Pubkey) -> bool {
+    let (a, b) = (pubkey::as_bytes(a), pubkey::as_bytes(b));
+    let mut i = 0;
+    while i < 32 {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Compares two public keys, like `Ord::cmp` (i.e., lexicographically by bytes) but usable in const contexts.
+pub const fn pubkey_cmp(a: &Pubkey, b: &Pubkey) -> Ordering {
+    let (a, b) = (pubkey::as_bytes(a), pubkey::as_bytes(b));
+    let mut i = 0;
+    while i < 32 {
+        if a[i] < b[i] {
+            return Ordering::Less;
+        }
+        if a[i] > b[i] {
+            return Ordering::Greater;
+        }
+        i += 1;
+    }
+    Ordering::Equal
+}
+
+/// Returns whether a public key is `Pubkey::default()`, i.e., all zeros (which is also the System Program ID).
+pub const fn pubkey_is_default(pubkey: &Pubkey) -> bool {
+    let bytes = pubkey::as_bytes(pubkey);
+    let mut i = 0;
+    while i < 32 {
+        if bytes[i] != 0 {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Returns the indices `(i, j)`, `i < j`, of the first pair of equal public keys, or `None` if all of them are
+/// unique.
+///
+/// See [`assert_unique_pubkeys`].
+pub const fn find_duplicate_pubkeys(pubkeys: &[Pubkey]) -> Option<(usize, usize)> {
+    let mut j = 1;
+    while j < pubkeys.len() {
+        let mut i = 0;
+        while i < j {
+            if pubkey_eq(&pubkeys[i], &pubkeys[j]) {
+                return Some((i, j));
+            }
+            i += 1;
+        }
+        j += 1;
+    }
+    None
+}
+
+/// Asserts that all public keys are unique.
+///
+/// This catches duplicates pasted into signer lists, which would silently weaken multisig thresholds. Use it in a
+/// const item so that duplicates fail compilation. For example:
+///
+/// ```ignore
+/// use const_str_to_pubkey::{assert_unique_pubkeys, pubkeys};
+///
+/// const SIGNERS: &[Pubkey] = &pubkeys!(env!("SIGNERS"));
+/// const _: () = assert_unique_pubkeys(SIGNERS);
+/// ```
+///
+/// # Panics
+///
+/// Panics with the indices and the Base58 form of the first duplicate public key found.
+pub const fn assert_unique_pubkeys(pubkeys: &[Pubkey]) {
+    if let Some((i, j)) = find_duplicate_pubkeys(pubkeys) {
+        let message = ConstMessage::<128>::new()
+            .push_str("Duplicate public keys at indices ")
+            .push_usize(i)
+            .push_str(" and ")
+            .push_usize(j)
+            .push_str(": ")
+            .push_str(pubkey_to_str(&pubkeys[j]).as_str());
+        panic!("{}", message.as_str());
+    }
+}
+
+/// Asserts at compile time that two constant public keys are not equal.
+///
+/// The macro expands to a `const _: () = ...;` item, so it can be used at module level or in a function body, and
+/// its arguments must be constant expressions. On failure, compilation stops with both keys in Base58. For example:
+///
+/// ```
+/// use const_str_to_pubkey::{assert_pubkey_ne, str_to_pubkey, Pubkey};
+///
+/// const ADMIN: Pubkey = str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y");
+/// const SYSTEM_PROGRAM: Pubkey = str_to_pubkey("11111111111111111111111111111111");
+/// assert_pubkey_ne!(ADMIN, SYSTEM_PROGRAM);
+/// ```
+///
+/// ```compile_fail
+/// use const_str_to_pubkey::{assert_pubkey_ne, str_to_pubkey, Pubkey};
+///
+/// const ADMIN: Pubkey = str_to_pubkey("11111111111111111111111111111111");
+/// const SYSTEM_PROGRAM: Pubkey = str_to_pubkey("11111111111111111111111111111111");
+/// assert_pubkey_ne!(ADMIN, SYSTEM_PROGRAM);
+/// ```
+#[macro_export]
+macro_rules! assert_pubkey_ne {
+    ($left:expr, $right:expr $(,)?) => {
+        const _: () = $crate::__private::assert_pubkey_ne(
+            &$left,
+            &$right,
+            concat!(stringify!($left), " != ", stringify!($right)),
+        );
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pubkeys;
+
+    #[test]
+    fn test_unique_pubkeys() {
+        const SIGNERS: &[Pubkey] = &pubkeys!(
+            "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y, 11111111111111111111111111111111, TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        );
+        const _: () = assert_unique_pubkeys(SIGNERS);
+        assert_eq!(find_duplicate_pubkeys(SIGNERS), None);
+        assert_eq!(find_duplicate_pubkeys(&[]), None);
+
+        let mut duplicated = SIGNERS.to_vec();
+        duplicated.push(SIGNERS[1]);
+        assert_eq!(find_duplicate_pubkeys(&duplicated), Some((1, 3)));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Duplicate public keys at indices 0 and 2: CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y"
+    )]
+    fn test_duplicate_pubkeys() {
+        assert_unique_pubkeys(&pubkeys!(
+            "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y, 11111111111111111111111111111111, CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y"
+        ));
+    }
+
+    #[test]
+    fn test_pubkey_cmp() {
+        let mut state = crate::sha256::hash(b"test_pubkey_cmp");
+        let pubkeys: Vec<Pubkey> = (0..32)
+            .map(|i| {
+                state = crate::sha256::hash(&state);
+                // Share prefixes of different lengths
+                let mut bytes = state;
+                bytes[..i % 32].fill(0);
+                pubkey::from_bytes(bytes)
+            })
+            .collect();
+        for a in &pubkeys {
+            for b in &pubkeys {
+                assert_eq!(pubkey_eq(a, b), a == b);
+                assert_eq!(pubkey_cmp(a, b), a.cmp(b));
+            }
+            assert!(!pubkey_is_default(a));
+        }
+        assert!(pubkey_is_default(&Pubkey::default()));
+        const SYSTEM_PROGRAM: Pubkey = crate::str_to_pubkey("11111111111111111111111111111111");
+        assert_pubkey_ne!(crate::PLACEHOLDER_PUBKEY, SYSTEM_PROGRAM);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assertion `ADMIN != SYSTEM_PROGRAM` failed\n  left: 11111111111111111111111111111111\n right: 11111111111111111111111111111111"
+    )]
+    fn test_assert_pubkey_ne() {
+        const ADMIN: Pubkey = pubkey::from_bytes([0; 32]);
+        const SYSTEM_PROGRAM: Pubkey = crate::str_to_pubkey("11111111111111111111111111111111");
+        crate::__private::assert_pubkey_ne(&ADMIN, &SYSTEM_PROGRAM, "ADMIN != SYSTEM_PROGRAM");
+    }
+}
@@ -0,0 +1,571 @@
+//! Reading public keys out of a config file at compile time, e.g., `include_str!("addresses.toml")`.
+
+use crate::{
+    base58::try_decode_base58_bytes,
+    bytes::{bytes_eq, skip_whitespace, subslice},
+    message::ConstMessage,
+    pubkey::{self, Pubkey},
+    DecodePubkeyError,
+};
+
+/// The error returned by [`try_config_pubkey`] when the public key cannot be read out of a config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The config is neither `key = "value"` lines nor a flat JSON object of strings.
+    InvalidSyntax {
+        /// Byte index of the offending character in the config, or its length if it ends too early.
+        index: usize,
+    },
+    /// The key is not in the config.
+    MissingKey,
+    /// A key is in the config more than once.
+    DuplicateKey {
+        /// Byte index of the second occurrence of the key in the config.
+        index: usize,
+    },
+    /// A value of the config is not a valid public key.
+    InvalidPubkey {
+        /// Byte index of the value in the config.
+        index: usize,
+        /// Why the value is not a valid public key.
+        error: DecodePubkeyError,
+    },
+}
+
+impl ConfigError {
+    /// Returns a static description of the error.
+    pub const fn message(&self) -> &'static str {
+        match self {
+            ConfigError::InvalidSyntax { .. } => {
+                "Config must be `key = \"value\"` lines or a flat JSON object of strings"
+            }
+            ConfigError::MissingKey => "Key not found in config",
+            ConfigError::DuplicateKey { .. } => "Key found more than once in config",
+            ConfigError::InvalidPubkey { error, .. } => error.message(),
+        }
+    }
+}
+
+impl core::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConfigError::InvalidSyntax { index } | ConfigError::DuplicateKey { index } => {
+                write!(f, "{} (at index {})", self.message(), index)
+            }
+            ConfigError::InvalidPubkey { index, error } => {
+                write!(f, "{} (value at index {})", error, index)
+            }
+            ConfigError::MissingKey => f.write_str(self.message()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConfigError {}
+
+const fn skip_spaces(s: &[u8], mut i: usize) -> usize {
+    while i < s.len() && matches!(s[i], b' ' | b'\t') {
+        i += 1;
+    }
+    i
+}
+
+const fn expect(s: &[u8], i: usize, byte: u8) -> Result<usize, ConfigError> {
+    if i < s.len() && s[i] == byte {
+        Ok(i + 1)
+    } else {
+        Err(ConfigError::InvalidSyntax { index: i })
+    }
+}
+
+/// Parses the string whose opening quote is at `i`, returning the byte range of its content and the index after
+/// the closing quote. Escapes are not supported.
+const fn parse_string(s: &[u8], i: usize) -> Result<(usize, usize, usize), ConfigError> {
+    let begin = match expect(s, i, b'"') {
+        Ok(begin) => begin,
+        Err(err) => return Err(err),
+    };
+    let mut end = begin;
+    while end < s.len() && s[end] != b'"' {
+        if matches!(s[end], b'\\' | b'\n' | b'\r') {
+            return Err(ConfigError::InvalidSyntax { index: end });
+        }
+        end += 1;
+    }
+    match expect(s, end, b'"') {
+        Ok(next) => Ok((begin, end, next)),
+        Err(err) => Err(err),
+    }
+}
+
+const fn is_key_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-'
+}
+
+/// Parses the key at `i`, bare or quoted, returning its byte range and the index after it.
+///
+/// Either way, keys are made of ASCII letters, digits, `_` and `-`, so that they are valid environment variable
+/// names for the `build` module.
+const fn parse_key(s: &[u8], i: usize) -> Result<(usize, usize, usize), ConfigError> {
+    let (begin, end, next) = if i < s.len() && s[i] == b'"' {
+        match parse_string(s, i) {
+            Ok(string) => string,
+            Err(err) => return Err(err),
+        }
+    } else {
+        let mut end = i;
+        while end < s.len() && is_key_byte(s[end]) {
+            end += 1;
+        }
+        (i, end, end)
+    };
+    if begin == end {
+        return Err(ConfigError::InvalidSyntax { index: begin });
+    }
+    let mut j = begin;
+    while j < end {
+        if !is_key_byte(s[j]) {
+            return Err(ConfigError::InvalidSyntax { index: j });
+        }
+        j += 1;
+    }
+    Ok((begin, end, next))
+}
+
+/// Skips an optional comment at `i`, then expects the end of the line, returning the index of the next line.
+const fn end_of_line(s: &[u8], mut i: usize) -> Result<usize, ConfigError> {
+    if i < s.len() && s[i] == b'#' {
+        while i < s.len() && s[i] != b'\n' {
+            i += 1;
+        }
+    }
+    if i < s.len() && s[i] == b'\r' {
+        i += 1;
+    }
+    if i == s.len() {
+        return Ok(i);
+    }
+    expect(s, i, b'\n')
+}
+
+/// An entry of a config, as the byte ranges of its key and value.
+#[derive(Clone, Copy)]
+pub(crate) struct ConfigEntry {
+    pub(crate) key: (usize, usize),
+    pub(crate) value: (usize, usize),
+}
+
+/// Where [`next_entry`] parses the next entry of a config.
+#[derive(Clone, Copy)]
+pub(crate) struct ConfigCursor {
+    index: usize,
+    json: bool,
+    first: bool,
+    done: bool,
+}
+
+/// Returns the cursor before the first entry of a config, detecting whether it is a JSON object.
+pub(crate) const fn config_start(s: &[u8]) -> ConfigCursor {
+    let start = skip_whitespace(s, 0);
+    let json = start < s.len() && s[start] == b'{';
+    ConfigCursor {
+        index: if json { start + 1 } else { 0 },
+        json,
+        first: true,
+        done: false,
+    }
+}
+
+/// Parses the entry of a flat JSON object at `cursor`.
+const fn next_json_entry(
+    s: &[u8],
+    cursor: ConfigCursor,
+) -> Result<Option<(ConfigEntry, ConfigCursor)>, ConfigError> {
+    let mut i = skip_whitespace(s, cursor.index);
+    let mut next_cursor = ConfigCursor {
+        first: false,
+        ..cursor
+    };
+    let entry = if cursor.first && i < s.len() && s[i] == b'}' {
+        next_cursor.done = true;
+        None
+    } else {
+        if i < s.len() && s[i] != b'"' {
+            return Err(ConfigError::InvalidSyntax { index: i });
+        }
+        let (key_begin, key_end, next) = match parse_key(s, i) {
+            Ok(key) => key,
+            Err(err) => return Err(err),
+        };
+        i = match expect(s, skip_whitespace(s, next), b':') {
+            Ok(i) => skip_whitespace(s, i),
+            Err(err) => return Err(err),
+        };
+        let (value_begin, value_end, next) = match parse_string(s, i) {
+            Ok(string) => string,
+            Err(err) => return Err(err),
+        };
+
+        i = skip_whitespace(s, next);
+        if i == s.len() {
+            return Err(ConfigError::InvalidSyntax { index: i });
+        }
+        match s[i] {
+            b',' => {}
+            b'}' => next_cursor.done = true,
+            _ => return Err(ConfigError::InvalidSyntax { index: i }),
+        }
+        Some(ConfigEntry {
+            key: (key_begin, key_end),
+            value: (value_begin, value_end),
+        })
+    };
+
+    // Only whitespace may follow the closing brace
+    if next_cursor.done {
+        let end = skip_whitespace(s, i + 1);
+        if end < s.len() {
+            return Err(ConfigError::InvalidSyntax { index: end });
+        }
+    }
+    next_cursor.index = i + 1;
+    match entry {
+        Some(entry) => Ok(Some((entry, next_cursor))),
+        None => Ok(None),
+    }
+}
+
+/// Parses the next `key = "value"` line at or after `cursor`, skipping blank lines and comments.
+const fn next_line_entry(
+    s: &[u8],
+    cursor: ConfigCursor,
+) -> Result<Option<(ConfigEntry, ConfigCursor)>, ConfigError> {
+    let mut i = cursor.index;
+    loop {
+        i = skip_spaces(s, i);
+        if i == s.len() {
+            return Ok(None);
+        }
+        if !matches!(s[i], b'#' | b'\r' | b'\n') {
+            break;
+        }
+        i = match end_of_line(s, i) {
+            Ok(i) => i,
+            Err(err) => return Err(err),
+        };
+    }
+
+    let (key_begin, key_end, next) = match parse_key(s, i) {
+        Ok(key) => key,
+        Err(err) => return Err(err),
+    };
+    i = match expect(s, skip_spaces(s, next), b'=') {
+        Ok(i) => skip_spaces(s, i),
+        Err(err) => return Err(err),
+    };
+    let (value_begin, value_end, next) = match parse_string(s, i) {
+        Ok(string) => string,
+        Err(err) => return Err(err),
+    };
+    let index = match end_of_line(s, skip_spaces(s, next)) {
+        Ok(i) => i,
+        Err(err) => return Err(err),
+    };
+
+    let entry = ConfigEntry {
+        key: (key_begin, key_end),
+        value: (value_begin, value_end),
+    };
+    Ok(Some((entry, ConfigCursor { index, ..cursor })))
+}
+
+/// Parses the entry at `cursor`, returning it and the cursor after it, or `None` after the last entry.
+pub(crate) const fn next_entry(
+    s: &[u8],
+    cursor: ConfigCursor,
+) -> Result<Option<(ConfigEntry, ConfigCursor)>, ConfigError> {
+    if cursor.done {
+        Ok(None)
+    } else if cursor.json {
+        next_json_entry(s, cursor)
+    } else {
+        next_line_entry(s, cursor)
+    }
+}
+
+/// Returns the bytes in `range` of `s`.
+pub(crate) const fn range(s: &[u8], range: (usize, usize)) -> &[u8] {
+    subslice(s, range.0, range.1)
+}
+
+/// Returns the 1-based line number of the byte at `index`.
+pub(crate) const fn line_of(s: &[u8], index: usize) -> usize {
+    let mut line = 1;
+    let mut i = 0;
+    while i < index && i < s.len() {
+        if s[i] == b'\n' {
+            line += 1;
+        }
+        i += 1;
+    }
+    line
+}
+
+/// Reads the public key of `key` out of a config, returning an error instead of panicking when it cannot.
+///
+/// This is one pass over the config: it checks the syntax of every entry, but only decodes the value of `key`, and
+/// only rejects duplicates of `key`. See [`config_pubkey`].
+pub const fn try_config_pubkey(config: &str, key: &str) -> Result<Pubkey, ConfigError> {
+    let s = config.as_bytes();
+    let mut found: Option<ConfigEntry> = None;
+    let mut cursor = config_start(s);
+    loop {
+        match next_entry(s, cursor) {
+            Ok(Some((entry, next))) => {
+                if bytes_eq(range(s, entry.key), key.as_bytes()) {
+                    if found.is_some() {
+                        return Err(ConfigError::DuplicateKey { index: entry.key.0 });
+                    }
+                    found = Some(entry);
+                }
+                cursor = next;
+            }
+            Ok(None) => break,
+            Err(err) => return Err(err),
+        }
+    }
+
+    match found {
+        Some(entry) => match try_decode_base58_bytes(range(s, entry.value)) {
+            Ok(bytes) => Ok(pubkey::from_bytes(bytes)),
+            Err(error) => Err(ConfigError::InvalidPubkey {
+                index: entry.value.0,
+                error,
+            }),
+        },
+        None => Err(ConfigError::MissingKey),
+    }
+}
+
+/// Reads the public key of `key` out of a config, i.e., either `key = "base58"` lines (a flat TOML file) or a flat
+/// JSON object of strings.
+///
+/// TOML lines may have `#` comments. Keys may be bare or quoted, and are made of ASCII letters, digits, `_` and `-`.
+/// Sections, escapes and values other than strings are not supported. The value of `key` is decoded like
+/// [`str_to_pubkey`](crate::str_to_pubkey), and `key` must appear once. The other entries are only checked for
+/// syntax, so that reading a key costs a single pass over the config. `build::emit_config_pubkeys` uses the same
+/// syntax and also checks every value and every key for duplicates, so a whole config can be checked in `build.rs`.
+///
+/// Use the [`config_pubkey!`](crate::config_pubkey!) macro to read the key in a constant, e.g.,
+/// `config_pubkey!(include_str!("addresses.toml"), "admin")`.
+///
+/// # Panics
+///
+/// Panics if the config is invalid, or does not contain the key exactly once with a valid public key, with a message
+/// including the key and line. In a const context, this is a compile error.
+pub const fn config_pubkey(config: &str, key: &str) -> Pubkey {
+    match try_config_pubkey(config, key) {
+        Ok(pubkey) => pubkey,
+        Err(err) => {
+            let s = config.as_bytes();
+            let message = match err {
+                ConfigError::InvalidSyntax { index } => ConstMessage::<256>::new()
+                    .push_str(err.message())
+                    .push_str(" (at line ")
+                    .push_usize(line_of(s, index))
+                    .push_str(")"),
+                ConfigError::MissingKey => ConstMessage::<256>::new()
+                    .push_str("Key `")
+                    .push_str(key)
+                    .push_str("` not found in config"),
+                ConfigError::DuplicateKey { index } => ConstMessage::<256>::new()
+                    .push_str("Key `")
+                    .push_str(key)
+                    .push_str("` found more than once in config (at line ")
+                    .push_usize(line_of(s, index))
+                    .push_str(")"),
+                ConfigError::InvalidPubkey { index, error } => ConstMessage::<256>::new()
+                    .push_str("Invalid public key for `")
+                    .push_str(key)
+                    .push_str("` in config (at line ")
+                    .push_usize(line_of(s, index))
+                    .push_str("): ")
+                    .push_str(error.message()),
+            };
+            panic!("{}", message.as_str());
+        }
+    }
+}
+
+/// Reads a constant public key out of a config file at compile time.
+///
+/// The arguments must be constant `&str` expressions, usually `include_str!` of a file of `key = "base58"` lines or
+/// a flat JSON object. See [`config_pubkey`](crate::config_pubkey()). For example:
+///
+/// ```
+/// use const_str_to_pubkey::{config_pubkey, str_to_pubkey, Pubkey};
+///
+/// // Usually `include_str!("addresses.toml")`
+/// const ADDRESSES: &str = r#"
+/// ## Admin of the program
+/// admin = "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y"
+/// mint = "So11111111111111111111111111111111111111112"
+/// "#;
+///
+/// const ADMIN: Pubkey = config_pubkey!(ADDRESSES, "admin");
+/// assert_eq!(ADMIN, str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y"));
+/// ```
+#[macro_export]
+macro_rules! config_pubkey {
+    ($config:expr, $key:expr) => {{
+        const PUBKEY: $crate::__private::Pubkey = $crate::config_pubkey($config, $key);
+        PUBKEY
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::str_to_pubkey;
+
+    const ADMIN: &str = "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y";
+    const MINT: &str = "So11111111111111111111111111111111111111112";
+
+    #[test]
+    fn test_config_pubkey_toml() {
+        const TOML: &str = "# Addresses\r\n\r\nadmin = \"CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y\" # Admin\r\n\t\"token-mint\"=\"So11111111111111111111111111111111111111112\"";
+        assert_eq!(config_pubkey!(TOML, "admin"), str_to_pubkey(ADMIN));
+        assert_eq!(config_pubkey(TOML, "token-mint"), str_to_pubkey(MINT));
+        assert_eq!(
+            try_config_pubkey(TOML, "mint"),
+            Err(ConfigError::MissingKey)
+        );
+        assert_eq!(try_config_pubkey("", "admin"), Err(ConfigError::MissingKey));
+
+        assert_eq!(
+            try_config_pubkey(
+                "admin = \"CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ0rGB4Y\"",
+                "admin"
+            ),
+            Err(ConfigError::InvalidPubkey {
+                index: 9,
+                error: DecodePubkeyError::InvalidCharacter {
+                    index: 38,
+                    character: b'0'
+                }
+            })
+        );
+        // Only the entry read is decoded and checked for duplicates
+        let duplicate = format!("admin = \"{ADMIN}\"\nmint = \"{MINT}\"\nmint = \"2\"");
+        assert_eq!(
+            try_config_pubkey(&duplicate, "admin"),
+            Ok(str_to_pubkey(ADMIN))
+        );
+        assert_eq!(
+            try_config_pubkey(&duplicate, "mint"),
+            Err(ConfigError::DuplicateKey { index: 108 })
+        );
+        assert_eq!(
+            try_config_pubkey(&format!("admin = \"{ADMIN}\"\nmint = \"2\""), "mint"),
+            Err(ConfigError::InvalidPubkey {
+                index: 63,
+                error: DecodePubkeyError::TooShort
+            })
+        );
+        // The syntax of every entry is checked, even after the one read
+        assert_eq!(
+            try_config_pubkey(&format!("admin = \"{ADMIN}\"\nmint = 2"), "admin"),
+            Err(ConfigError::InvalidSyntax { index: 62 })
+        );
+        assert_eq!(
+            try_config_pubkey("\"admin key\" = \"2\"", "admin key"),
+            Err(ConfigError::InvalidSyntax { index: 6 })
+        );
+        assert_eq!(
+            try_config_pubkey("[addresses]\nadmin = \"2\"", "admin"),
+            Err(ConfigError::InvalidSyntax { index: 0 })
+        );
+        assert_eq!(
+            try_config_pubkey("admin = 2", "admin"),
+            Err(ConfigError::InvalidSyntax { index: 8 })
+        );
+        assert_eq!(
+            try_config_pubkey("admin = \"2\" mint = \"2\"", "admin"),
+            Err(ConfigError::InvalidSyntax { index: 12 })
+        );
+    }
+
+    #[test]
+    fn test_config_pubkey_json() {
+        let json = format!("\n{{\n  \"admin\": \"{ADMIN}\",\n  \"mint\" : \"{MINT}\"\n}}\n");
+        assert_eq!(try_config_pubkey(&json, "admin"), Ok(str_to_pubkey(ADMIN)));
+        assert_eq!(try_config_pubkey(&json, "mint"), Ok(str_to_pubkey(MINT)));
+        assert_eq!(
+            try_config_pubkey(&json, "oracle"),
+            Err(ConfigError::MissingKey)
+        );
+        assert_eq!(
+            try_config_pubkey("{}", "admin"),
+            Err(ConfigError::MissingKey)
+        );
+
+        assert_eq!(
+            try_config_pubkey("{\"admin\": \"2\",}", "admin"),
+            Err(ConfigError::InvalidSyntax { index: 14 })
+        );
+        assert_eq!(
+            try_config_pubkey("{\"admin\": 2}", "admin"),
+            Err(ConfigError::InvalidSyntax { index: 10 })
+        );
+        assert_eq!(
+            try_config_pubkey("{\"admin\": \"2\"", "admin"),
+            Err(ConfigError::InvalidSyntax { index: 13 })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Key `oracle` not found in config")]
+    fn test_missing_key() {
+        config_pubkey(
+            "admin = \"CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y\"",
+            "oracle",
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid public key for `mint` in config (at line 3): Base58 string cannot be empty"
+    )]
+    fn test_invalid_value() {
+        config_pubkey(
+            "{\n  \"admin\": \"CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y\",\n  \"mint\": \"\"\n}",
+            "mint",
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Key `admin` found more than once in config (at line 2)")]
+    fn test_duplicate_key() {
+        config_pubkey(
+            "admin = \"CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y\"\nadmin = \"11111111111111111111111111111111\"",
+            "admin",
+        );
+    }
+
+    #[test]
+    fn test_large_config() {
+        // Reading a key is one pass over the config, so a few hundred entries stay well within the limits of const
+        // evaluation, even when several keys are read
+        const CONFIG: &str = include_str!("../testdata/addresses.toml");
+        const FIRST: Pubkey = config_pubkey!(CONFIG, "key_000");
+        const MIDDLE: Pubkey = config_pubkey!(CONFIG, "key_150");
+        const LAST: Pubkey = config_pubkey!(CONFIG, "key_299");
+        assert_eq!(FIRST, str_to_pubkey(ADMIN));
+        assert_eq!(MIDDLE, str_to_pubkey(ADMIN));
+        assert_eq!(LAST, str_to_pubkey(MINT));
+        assert_eq!(
+            try_config_pubkey(CONFIG, "key_300"),
+            Err(ConfigError::MissingKey)
+        );
+    }
+}
@@ -0,0 +1,179 @@
+//! A public key decoded at compile time together with its `u64` limbs, for branchless comparisons.
+
+#[cfg(any(
+    feature = "solana-program",
+    feature = "solana-program-1",
+    feature = "anchor-lang"
+))]
+use crate::pubkey::solana::account_info::AccountInfo;
+use crate::{
+    pubkey::{self, Pubkey},
+    str_to_pubkey,
+};
+
+/// A [`Pubkey`](https://docs.rs/solana-program/latest/solana_program/pubkey/struct.Pubkey.html) with its bytes also
+/// stored as four native-endian `u64` limbs, precomputed at compile time.
+///
+/// Comparing with [`matches`](ConstPubkey::matches) loads the other key as four unaligned `u64`s and compares them
+/// with the limbs without branching, instead of comparing 32 bytes with `==`. Whether this costs fewer compute units
+/// on-chain than `==` has not been measured, so log `sol_log_compute_units()` around both in a program to check
+/// before relying on it. For example:
+///
+/// ```ignore
+/// use const_str_to_pubkey::{str_to_const_pubkey, ConstPubkey};
+///
+/// const ADMIN: ConstPubkey = str_to_const_pubkey(env!("ADMIN_PUBKEY"));
+///
+/// if !ADMIN.matches_account(admin_info) || !admin_info.is_signer {
+///     return Err(ProgramError::MissingRequiredSignature);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstPubkey {
+    pubkey: Pubkey,
+    limbs: [u64; 4],
+}
+
+impl ConstPubkey {
+    /// Wraps a public key, computing its limbs.
+    pub const fn new(pubkey: Pubkey) -> Self {
+        let bytes = pubkey::as_bytes(&pubkey);
+        let mut limbs = [0u64; 4];
+        let mut i = 0;
+        while i < 4 {
+            let mut limb = [0u8; 8];
+            let mut j = 0;
+            while j < 8 {
+                limb[j] = bytes[8 * i + j];
+                j += 1;
+            }
+            limbs[i] = u64::from_ne_bytes(limb);
+            i += 1;
+        }
+        Self { pubkey, limbs }
+    }
+
+    /// Returns the public key.
+    pub const fn pubkey(&self) -> &Pubkey {
+        &self.pubkey
+    }
+
+    /// Returns the bytes of the public key as four native-endian `u64`s.
+    pub const fn limbs(&self) -> &[u64; 4] {
+        &self.limbs
+    }
+
+    /// Returns whether `pubkey` is equal to this public key, comparing four `u64`s.
+    #[inline(always)]
+    pub fn matches(&self, pubkey: &Pubkey) -> bool {
+        let ptr = pubkey::as_bytes(pubkey).as_ptr() as *const u64;
+        // SAFETY: `ptr` points to 32 readable bytes, and unaligned reads do not require alignment.
+        let (a, b, c, d) = unsafe {
+            (
+                ptr.read_unaligned(),
+                ptr.add(1).read_unaligned(),
+                ptr.add(2).read_unaligned(),
+                ptr.add(3).read_unaligned(),
+            )
+        };
+        ((a ^ self.limbs[0]) | (b ^ self.limbs[1]) | (c ^ self.limbs[2]) | (d ^ self.limbs[3])) == 0
+    }
+
+    /// Returns whether the key of `account` is equal to this public key. See [`matches`](ConstPubkey::matches).
+    #[cfg(any(
+        feature = "solana-program",
+        feature = "solana-program-1",
+        feature = "anchor-lang"
+    ))]
+    #[inline(always)]
+    pub fn matches_account(&self, account: &AccountInfo) -> bool {
+        self.matches(account.key)
+    }
+}
+
+impl core::ops::Deref for ConstPubkey {
+    type Target = Pubkey;
+
+    fn deref(&self) -> &Pubkey {
+        &self.pubkey
+    }
+}
+
+impl From<ConstPubkey> for Pubkey {
+    fn from(const_pubkey: ConstPubkey) -> Self {
+        const_pubkey.pubkey
+    }
+}
+
+/// Converts a `&'static str` to a [`ConstPubkey`], i.e., [`str_to_pubkey`] with precomputed limbs for
+/// branchless comparisons.
+///
+/// # Panics
+///
+/// Panics like [`str_to_pubkey`] if the string is not a valid public key. In a const context, this is a compile
+/// error.
+pub const fn str_to_const_pubkey(s: &'static str) -> ConstPubkey {
+    ConstPubkey::new(str_to_pubkey(s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADMIN: ConstPubkey = str_to_const_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y");
+
+    #[test]
+    fn test_const_pubkey() {
+        let admin = *ADMIN.pubkey();
+        assert!(ADMIN.matches(&admin));
+        assert_eq!(Pubkey::from(ADMIN), admin);
+
+        // A difference in any byte is detected
+        for i in 0..32 {
+            let mut bytes = *pubkey::as_bytes(&admin);
+            bytes[i] ^= 0x80;
+            assert!(!ADMIN.matches(&pubkey::from_bytes(bytes)));
+        }
+
+        // Keys at an odd address, e.g., inside account data. The buffer is 8-byte aligned, so the key at offset 1 is
+        // misaligned for `u64` loads
+        #[repr(C, align(8))]
+        struct Buffer([u8; 33]);
+        fn key_at_offset_1(buffer: &Buffer) -> &Pubkey {
+            let ptr = buffer.0[1..].as_ptr() as *const Pubkey;
+            assert_eq!(ptr as usize % 8, 1);
+            // SAFETY: `ptr` points to 32 initialized bytes, and `Pubkey` is 32 bytes with an alignment of 1
+            unsafe { &*ptr }
+        }
+        assert_eq!(core::mem::align_of::<Pubkey>(), 1);
+        let mut buffer = Buffer([0; 33]);
+        buffer.0[1..].copy_from_slice(pubkey::as_bytes(&admin));
+        assert!(ADMIN.matches(key_at_offset_1(&buffer)));
+        buffer.0[32] ^= 1;
+        assert!(!ADMIN.matches(key_at_offset_1(&buffer)));
+    }
+
+    #[cfg(any(
+        feature = "solana-program",
+        feature = "solana-program-1",
+        feature = "anchor-lang"
+    ))]
+    #[test]
+    fn test_matches_account() {
+        let admin = *ADMIN.pubkey();
+        let mut lamports = 0;
+        let mut data = [];
+        let owner = Pubkey::default();
+        let account = AccountInfo::new(
+            &admin,
+            true,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        assert!(ADMIN.matches_account(&account));
+    }
+}
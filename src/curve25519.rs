@@ -0,0 +1,235 @@
+//! Just enough arithmetic over the field of curve25519 to tell whether 32 bytes are a valid ed25519 point, in const
+//! contexts.
+
+/// An element of the field modulo `p = 2^255 - 19`, stored as five 51-bit limbs, least significant first.
+///
+/// Limbs may exceed 51 bits between operations. [`FieldElement::reduce`] brings them back to a canonical value.
+#[derive(Clone, Copy)]
+struct FieldElement([u64; 5]);
+
+const LOW_51_BIT_MASK: u64 = (1 << 51) - 1;
+
+/// The curve constant `d = -121665 / 121666` of ed25519.
+const EDWARDS_D: FieldElement = FieldElement([
+    929955233495203,
+    466365720129213,
+    1662059464998953,
+    2033849074728123,
+    1442794654840575,
+]);
+
+const ONE: FieldElement = FieldElement([1, 0, 0, 0, 0]);
+
+const fn load8(bytes: &[u8; 32], offset: usize) -> u64 {
+    let mut word = 0;
+    let mut i = 0;
+    while i < 8 {
+        word |= (bytes[offset + i] as u64) << (8 * i);
+        i += 1;
+    }
+    word
+}
+
+impl FieldElement {
+    /// Loads a little-endian integer, ignoring the highest bit like `curve25519-dalek` does.
+    const fn from_bytes(bytes: &[u8; 32]) -> Self {
+        Self([
+            load8(bytes, 0) & LOW_51_BIT_MASK,
+            (load8(bytes, 6) >> 3) & LOW_51_BIT_MASK,
+            (load8(bytes, 12) >> 6) & LOW_51_BIT_MASK,
+            (load8(bytes, 19) >> 1) & LOW_51_BIT_MASK,
+            (load8(bytes, 24) >> 12) & LOW_51_BIT_MASK,
+        ])
+    }
+
+    /// Carries the limbs so that each one fits in 51 bits, plus a tiny excess in the lowest limb.
+    const fn carry(mut limbs: [u64; 5]) -> Self {
+        limbs[1] += limbs[0] >> 51;
+        limbs[0] &= LOW_51_BIT_MASK;
+        limbs[2] += limbs[1] >> 51;
+        limbs[1] &= LOW_51_BIT_MASK;
+        limbs[3] += limbs[2] >> 51;
+        limbs[2] &= LOW_51_BIT_MASK;
+        limbs[4] += limbs[3] >> 51;
+        limbs[3] &= LOW_51_BIT_MASK;
+        limbs[0] += (limbs[4] >> 51) * 19;
+        limbs[4] &= LOW_51_BIT_MASK;
+        Self(limbs)
+    }
+
+    /// Returns the canonical limbs, i.e., of the value in `[0, p)`.
+    const fn reduce(self) -> [u64; 5] {
+        let mut limbs = Self::carry(self.0).0;
+
+        // `q` is 1 if the value is at least `p`, 0 otherwise
+        let mut q = (limbs[0] + 19) >> 51;
+        q = (limbs[1] + q) >> 51;
+        q = (limbs[2] + q) >> 51;
+        q = (limbs[3] + q) >> 51;
+        q = (limbs[4] + q) >> 51;
+
+        limbs[0] += 19 * q;
+        limbs[1] += limbs[0] >> 51;
+        limbs[0] &= LOW_51_BIT_MASK;
+        limbs[2] += limbs[1] >> 51;
+        limbs[1] &= LOW_51_BIT_MASK;
+        limbs[3] += limbs[2] >> 51;
+        limbs[2] &= LOW_51_BIT_MASK;
+        limbs[4] += limbs[3] >> 51;
+        limbs[3] &= LOW_51_BIT_MASK;
+        limbs[4] &= LOW_51_BIT_MASK;
+        limbs
+    }
+
+    const fn add(self, rhs: Self) -> Self {
+        let (a, b) = (self.0, rhs.0);
+        Self::carry([
+            a[0] + b[0],
+            a[1] + b[1],
+            a[2] + b[2],
+            a[3] + b[3],
+            a[4] + b[4],
+        ])
+    }
+
+    const fn sub(self, rhs: Self) -> Self {
+        // Add 16p first so that no limb underflows
+        let (a, b) = (self.0, rhs.0);
+        Self::carry([
+            (a[0] + 36028797018963664) - b[0],
+            (a[1] + 36028797018963952) - b[1],
+            (a[2] + 36028797018963952) - b[2],
+            (a[3] + 36028797018963952) - b[3],
+            (a[4] + 36028797018963952) - b[4],
+        ])
+    }
+
+    const fn mul(self, rhs: Self) -> Self {
+        const fn m(x: u64, y: u64) -> u128 {
+            (x as u128) * (y as u128)
+        }
+
+        let (a, b) = (self.0, rhs.0);
+        let b1_19 = b[1] * 19;
+        let b2_19 = b[2] * 19;
+        let b3_19 = b[3] * 19;
+        let b4_19 = b[4] * 19;
+
+        let c0 = m(a[0], b[0]) + m(a[4], b1_19) + m(a[3], b2_19) + m(a[2], b3_19) + m(a[1], b4_19);
+        let mut c1 =
+            m(a[1], b[0]) + m(a[0], b[1]) + m(a[4], b2_19) + m(a[3], b3_19) + m(a[2], b4_19);
+        let mut c2 =
+            m(a[2], b[0]) + m(a[1], b[1]) + m(a[0], b[2]) + m(a[4], b3_19) + m(a[3], b4_19);
+        let mut c3 = m(a[3], b[0]) + m(a[2], b[1]) + m(a[1], b[2]) + m(a[0], b[3]) + m(a[4], b4_19);
+        let mut c4 = m(a[4], b[0]) + m(a[3], b[1]) + m(a[2], b[2]) + m(a[1], b[3]) + m(a[0], b[4]);
+
+        c1 += c0 >> 51;
+        c2 += c1 >> 51;
+        c3 += c2 >> 51;
+        c4 += c3 >> 51;
+        let carry = (c4 >> 51) as u64;
+
+        let mut limbs = [
+            (c0 as u64) & LOW_51_BIT_MASK,
+            (c1 as u64) & LOW_51_BIT_MASK,
+            (c2 as u64) & LOW_51_BIT_MASK,
+            (c3 as u64) & LOW_51_BIT_MASK,
+            (c4 as u64) & LOW_51_BIT_MASK,
+        ];
+        limbs[0] += carry * 19;
+        limbs[1] += limbs[0] >> 51;
+        limbs[0] &= LOW_51_BIT_MASK;
+        Self(limbs)
+    }
+
+    /// Returns `self^(2^k)`.
+    const fn pow2k(self, k: u32) -> Self {
+        let mut x = self;
+        let mut i = 0;
+        while i < k {
+            x = x.mul(x);
+            i += 1;
+        }
+        x
+    }
+
+    /// Returns `self^((p - 1) / 2) = self^(2^254 - 10)`, i.e., the Legendre symbol of `self`: 1 for a non-zero
+    /// square, 0 for zero, and -1 otherwise.
+    ///
+    /// This is the addition chain of `curve25519-dalek`'s `pow22501`, followed by `2^4` and `* self^6`.
+    const fn legendre(self) -> Self {
+        let t0 = self.mul(self); // 2
+        let t1 = t0.pow2k(2); // 8
+        let t2 = self.mul(t1); // 9
+        let t3 = t0.mul(t2); // 11
+        let t4 = t3.mul(t3); // 22
+        let t5 = t2.mul(t4); // 2^5 - 1
+        let t7 = t5.pow2k(5).mul(t5); // 2^10 - 1
+        let t9 = t7.pow2k(10).mul(t7); // 2^20 - 1
+        let t11 = t9.pow2k(20).mul(t9); // 2^40 - 1
+        let t13 = t11.pow2k(10).mul(t7); // 2^50 - 1
+        let t15 = t13.pow2k(50).mul(t13); // 2^100 - 1
+        let t17 = t15.pow2k(100).mul(t15); // 2^200 - 1
+        let t19 = t17.pow2k(50).mul(t13); // 2^250 - 1
+        let t20 = t19.pow2k(4); // 2^254 - 16
+        t20.mul(t0.mul(t0.mul(t0))) // 2^254 - 10
+    }
+}
+
+const fn limbs_eq(a: [u64; 5], b: [u64; 5]) -> bool {
+    a[0] == b[0] && a[1] == b[1] && a[2] == b[2] && a[3] == b[3] && a[4] == b[4]
+}
+
+/// Returns whether `bytes` is the compressed form of a point on the ed25519 curve, like
+/// `solana_program::pubkey::bytes_are_curve_point`.
+///
+/// Program derived addresses are exactly the addresses for which this returns `false`. For example:
+///
+/// ```
+/// use const_str_to_pubkey::{bytes_are_curve_point, decode_base58};
+///
+/// // A wallet address is a point on the curve
+/// const WALLET: [u8; 32] = decode_base58("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y");
+/// assert!(bytes_are_curve_point(&WALLET));
+/// ```
+pub const fn bytes_are_curve_point(bytes: &[u8; 32]) -> bool {
+    // The point decompresses iff x^2 = u / v has a solution, where u = y^2 - 1 and v = d * y^2 + 1. Since v is never
+    // zero, that is iff u * v is zero or a square.
+    let y = FieldElement::from_bytes(bytes);
+    let yy = y.mul(y);
+    let u = yy.sub(ONE);
+    let v = yy.mul(EDWARDS_D).add(ONE);
+
+    let symbol = u.mul(v).legendre().reduce();
+    limbs_eq(symbol, [0; 5]) || limbs_eq(symbol, ONE.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_are_curve_point() {
+        // Identity point (y = 1) and its non-canonical encoding (y = p + 1)
+        let mut identity = [0u8; 32];
+        identity[0] = 1;
+        assert!(bytes_are_curve_point(&identity));
+        let mut identity_non_canonical = [0xFF; 32];
+        identity_non_canonical[0] = 0xEE;
+        identity_non_canonical[31] = 0x7F;
+        assert!(bytes_are_curve_point(&identity_non_canonical));
+
+        // The sign bit does not affect whether the point exists
+        for seed in 0u8..=255 {
+            let mut bytes = crate::sha256::hash(&[seed]);
+            let on_curve = bytes_are_curve_point(&bytes);
+            assert_eq!(
+                on_curve,
+                solana_program::pubkey::bytes_are_curve_point(bytes),
+                "{bytes:?}"
+            );
+            bytes[31] ^= 0x80;
+            assert_eq!(bytes_are_curve_point(&bytes), on_curve);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Reading the public key out of a keypair file written by `solana-keygen`, e.g., `target/deploy/<name>-keypair.json`.
+
+use crate::{
+    bytes::skip_whitespace,
+    bytes_are_curve_point,
+    pubkey::{self, Pubkey},
+};
+
+/// The error returned by [`try_keypair_json_to_pubkey`] when a string is not a valid keypair JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeypairJsonError {
+    /// The string does not start with `[`, or ends before the closing `]`.
+    NotAnArray,
+    /// The string contains a character that is not allowed at its position.
+    InvalidCharacter {
+        /// Byte index of the offending character in the string.
+        index: usize,
+        /// The offending byte.
+        character: u8,
+    },
+    /// A number of the array is larger than 255.
+    ByteOutOfRange {
+        /// Index of the offending number in the array.
+        index: usize,
+    },
+    /// The array does not contain exactly 64 numbers.
+    WrongLength {
+        /// The number of numbers in the array.
+        len: usize,
+    },
+    /// The last 32 numbers, i.e., the public key, are not a point on the ed25519 curve.
+    InvalidPublicKey,
+}
+
+impl KeypairJsonError {
+    /// Returns a static description of the error.
+    ///
+    /// This is the message [`keypair_json_to_pubkey`] panics with, so it can be used in const contexts.
+    pub const fn message(&self) -> &'static str {
+        match self {
+            KeypairJsonError::NotAnArray => "Keypair JSON must be an array of numbers",
+            KeypairJsonError::InvalidCharacter { .. } => "Invalid character found in keypair JSON",
+            KeypairJsonError::ByteOutOfRange { .. } => {
+                "Keypair JSON contains a number larger than 255"
+            }
+            KeypairJsonError::WrongLength { .. } => "Keypair JSON must contain exactly 64 numbers",
+            KeypairJsonError::InvalidPublicKey => {
+                "The public key in keypair JSON is not a point on the ed25519 curve"
+            }
+        }
+    }
+}
+
+impl core::fmt::Display for KeypairJsonError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            KeypairJsonError::InvalidCharacter { index, character } => write!(
+                f,
+                "{} (byte {:#04x} at index {})",
+                self.message(),
+                character,
+                index
+            ),
+            KeypairJsonError::ByteOutOfRange { index } => {
+                write!(f, "{} (at index {})", self.message(), index)
+            }
+            KeypairJsonError::WrongLength { len } => {
+                write!(f, "{} (found {})", self.message(), len)
+            }
+            _ => f.write_str(self.message()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for KeypairJsonError {}
+
+/// Parses a keypair JSON, i.e., an array of 64 numbers from 0 to 255, returning its bytes.
+///
+/// See [`try_keypair_json_to_pubkey`].
+pub const fn try_parse_keypair_json(s: &str) -> Result<[u8; 64], KeypairJsonError> {
+    let s = s.as_bytes();
+    let mut bytes = [0u8; 64];
+
+    let mut i = skip_whitespace(s, 0);
+    if i == s.len() || s[i] != b'[' {
+        return Err(KeypairJsonError::NotAnArray);
+    }
+    i = skip_whitespace(s, i + 1);
+
+    let mut len = 0;
+    if i < s.len() && s[i] == b']' {
+        i += 1;
+    } else {
+        loop {
+            if i == s.len() {
+                return Err(KeypairJsonError::NotAnArray);
+            }
+            if !s[i].is_ascii_digit() {
+                return Err(KeypairJsonError::InvalidCharacter {
+                    index: i,
+                    character: s[i],
+                });
+            }
+
+            let mut value = 0u32;
+            while i < s.len() && s[i].is_ascii_digit() {
+                value = value * 10 + (s[i] - b'0') as u32;
+                if value > u8::MAX as u32 {
+                    return Err(KeypairJsonError::ByteOutOfRange { index: len });
+                }
+                i += 1;
+            }
+            if len < 64 {
+                bytes[len] = value as u8;
+            }
+            len += 1;
+
+            i = skip_whitespace(s, i);
+            if i == s.len() {
+                return Err(KeypairJsonError::NotAnArray);
+            }
+            match s[i] {
+                b',' => i = skip_whitespace(s, i + 1),
+                b']' => {
+                    i += 1;
+                    break;
+                }
+                character => {
+                    return Err(KeypairJsonError::InvalidCharacter {
+                        index: i,
+                        character,
+                    })
+                }
+            }
+        }
+    }
+
+    i = skip_whitespace(s, i);
+    if i < s.len() {
+        return Err(KeypairJsonError::InvalidCharacter {
+            index: i,
+            character: s[i],
+        });
+    }
+    if len != 64 {
+        return Err(KeypairJsonError::WrongLength { len });
+    }
+    Ok(bytes)
+}
+
+/// Reads the public key out of a keypair JSON, returning an error instead of panicking when the string is not a
+/// valid keypair JSON.
+///
+/// See [`keypair_json_to_pubkey`].
+pub const fn try_keypair_json_to_pubkey(s: &str) -> Result<Pubkey, KeypairJsonError> {
+    let keypair = match try_parse_keypair_json(s) {
+        Ok(keypair) => keypair,
+        Err(err) => return Err(err),
+    };
+
+    let mut public = [0u8; 32];
+    let mut i = 0;
+    while i < 32 {
+        public[i] = keypair[32 + i];
+        i += 1;
+    }
+    if !bytes_are_curve_point(&public) {
+        return Err(KeypairJsonError::InvalidPublicKey);
+    }
+    Ok(pubkey::from_bytes(public))
+}
+
+/// Reads the public key out of a keypair JSON written by `solana-keygen`, i.e., an array of 64 numbers whose last
+/// 32 are the public key.
+///
+/// This lets the program ID follow the keypair that is checked in, instead of a hand-copied string. For example:
+///
+/// ```ignore
+/// use const_str_to_pubkey::keypair_json_to_pubkey;
+///
+/// pub const ID: Pubkey = keypair_json_to_pubkey(include_str!("../target/deploy/my_program-keypair.json"));
+/// ```
+///
+/// Only the public half is used. It is checked to be a point on the curve, but not against the secret half, which
+/// would take an ed25519 scalar multiplication.
+///
+/// # Panics
+///
+/// Panics with [`KeypairJsonError::message`] if the string is not a valid keypair JSON (see
+/// [`try_keypair_json_to_pubkey`]). In a const context, this is a compile error.
+pub const fn keypair_json_to_pubkey(s: &str) -> Pubkey {
+    match try_keypair_json_to_pubkey(s) {
+        Ok(pubkey) => pubkey,
+        Err(err) => panic!("{}", err.message()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEYPAIR: &str = "[136,127,172,0,6,84,64,207,4,120,161,114,25,242,198,128,135,176,154,78,194,15,147,100,188,125,23,171,14,43,253,169,125,178,176,48,201,144,223,139,2,172,90,120,65,4,252,184,146,129,202,69,176,208,193,100,150,119,211,188,133,156,189,91]";
+
+    #[test]
+    fn test_keypair_json_to_pubkey() {
+        const ID: Pubkey = keypair_json_to_pubkey(KEYPAIR);
+        assert_eq!(
+            ID,
+            crate::str_to_pubkey("9TfziDGboySLJTXXfip4kQWfPYkGBhnsZanvJfSTAzjk")
+        );
+
+        // Whitespace is allowed around the numbers, e.g., in pretty-printed files
+        let pretty = KEYPAIR
+            .replace('[', "[\n  ")
+            .replace(',', ",\n  ")
+            .replace(']', "\n]\n");
+        assert_eq!(try_keypair_json_to_pubkey(&pretty), Ok(ID));
+    }
+
+    #[test]
+    fn test_invalid_keypair_json() {
+        assert_eq!(
+            try_parse_keypair_json(""),
+            Err(KeypairJsonError::NotAnArray)
+        );
+        assert_eq!(
+            try_parse_keypair_json("[1, 2"),
+            Err(KeypairJsonError::NotAnArray)
+        );
+        assert_eq!(
+            try_parse_keypair_json("[]"),
+            Err(KeypairJsonError::WrongLength { len: 0 })
+        );
+        assert_eq!(
+            try_parse_keypair_json(&KEYPAIR.replace(']', ",1]")),
+            Err(KeypairJsonError::WrongLength { len: 65 })
+        );
+        assert_eq!(
+            try_parse_keypair_json("[1, 256]"),
+            Err(KeypairJsonError::ByteOutOfRange { index: 1 })
+        );
+        assert_eq!(
+            try_parse_keypair_json("[1, -2]"),
+            Err(KeypairJsonError::InvalidCharacter {
+                index: 4,
+                character: b'-'
+            })
+        );
+        assert_eq!(
+            try_parse_keypair_json("[1,]"),
+            Err(KeypairJsonError::InvalidCharacter {
+                index: 3,
+                character: b']'
+            })
+        );
+        assert_eq!(
+            try_parse_keypair_json(&format!("{KEYPAIR}]")),
+            Err(KeypairJsonError::InvalidCharacter {
+                index: KEYPAIR.len(),
+                character: b']'
+            })
+        );
+
+        // A keypair whose public half is not on the curve
+        let mut bytes = try_parse_keypair_json(KEYPAIR).unwrap();
+        while bytes_are_curve_point(bytes[32..].try_into().unwrap()) {
+            bytes[63] += 1;
+        }
+        let numbers: Vec<String> = bytes.iter().map(|byte| byte.to_string()).collect();
+        assert_eq!(
+            try_keypair_json_to_pubkey(&format!("[{}]", numbers.join(","))),
+            Err(KeypairJsonError::InvalidPublicKey)
+        );
+    }
+}
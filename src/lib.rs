@@ -35,54 +35,138 @@
 //! [env]
 //! ADMIN_PUBKEY = "AdminPubkey11111111111111111111111111111111"
 //! ```
+//!
+//! Alternatively, use the [`env_pubkey!`] macro, which falls back to a default public key when the environment variable is not set:
+//!
+//! ```ignore
+//! use const_str_to_pubkey::env_pubkey;
+//!
+//! const ADMIN_PUBKEY: Pubkey = env_pubkey!("ADMIN_PUBKEY", default = "AdminPubkey11111111111111111111111111111111");
+//! ```
+//!
+//! Without a default, `env_pubkey!("ADMIN_PUBKEY")` fails to compile with an error naming the variable, unless the crate using the macro declares a `placeholder-pubkey` feature (`placeholder-pubkey = []` in its `[features]`) and it is enabled, in which case it falls back to [`PLACEHOLDER_PUBKEY`]. The feature is checked per calling crate, so enabling it in, e.g., a test helper does not affect the program.
+//!
+//! ## Features
+//!
+//! By default, this crate depends on `solana-program`, and [`str_to_pubkey`] returns `solana_program::pubkey::Pubkey`. To avoid building all of `solana-program`, e.g., in client crates, disable the default features and:
+//!
+//! - enable `solana-pubkey` to depend only on `solana-pubkey`, with [`str_to_pubkey`] returning `solana_pubkey::Pubkey` (the same type as `solana_program::pubkey::Pubkey` in 2.x), or
+//! - enable neither, with [`str_to_pubkey`] returning `[u8; 32]`.
+//!
+//! Programs pinned to an older toolchain can select the `Pubkey` of their `solana-program` or `anchor-lang` version instead, with `default-features = false` and exactly one of:
+//!
+//! - `solana-program-1` for `solana-program` 1.16 to 1.18. The exact version is the one in `Cargo.lock`, i.e., the one the program depends on.
+//! - `anchor-lang` for `anchor_lang::prelude::Pubkey` (0.29 to 0.32).
+//!
+//! Enabling more than one of these (or one of them together with the default `solana-program`) is a compile error. `scripts/test-features.sh` runs the tests with each of them, pinning each supported version in the lockfile.
+//!
+//! The minimum supported Rust version is 1.68, the `rustc` that `cargo build-sbf` of Solana 1.16 and 1.17 uses, so that the crate builds with the toolchain of every supported `solana-program` version. `scripts/test-features.sh` compiles the crate with it as well.
+//!
+//! [`Pubkey`] is the type returned in each case. `str_to_hash` needs `solana-program` or `solana-program-1`, `ConstPubkey::matches_account` needs any of the features except `solana-pubkey`, and program derived addresses need any of them.
+//!
+//! ## `no_std`
+//!
+//! The crate is `#![no_std]`. With `default-features = false`, it has no dependencies at all, and [`str_to_pubkey_bytes`] decodes a public key to `[u8; 32]`, e.g., to share address constants with firmware that cannot link Solana crates. The `std` feature (enabled by `solana-program`) only adds the `std::error::Error` implementation of [`DecodeError`].
+//!
+//! ## Build scripts
+//!
+//! The `build` feature adds the `build` module, which validates public keys in environment variables or a config file from `build.rs`, so that a bad key fails the build with an error naming the variable and the offending character.
+
+#![cfg_attr(not(test), no_std)]
+
+#[cfg(feature = "std")]
+extern crate std;
 
-use solana_program::pubkey::Pubkey;
+#[cfg(all(
+    feature = "solana-program-1",
+    any(feature = "solana-program", feature = "anchor-lang")
+))]
+compile_error!(
+    "The `solana-program-1` feature cannot be combined with `solana-program` (enabled by default) or `anchor-lang`. \
+     Set `default-features = false` and enable only one of them"
+);
 
-/// Returns an array that represents a map from Base58 encoding character to number.
+#[cfg(all(feature = "anchor-lang", feature = "solana-program"))]
+compile_error!(
+    "The `anchor-lang` feature cannot be combined with `solana-program` (enabled by default). Set \
+     `default-features = false`"
+);
+
+mod base58;
+#[cfg(feature = "build")]
+pub mod build;
+mod bytes;
+mod cluster;
+mod cmp;
+mod config;
+mod const_pubkey;
+mod curve25519;
+mod keypair;
+mod list;
+mod map;
+mod message;
+#[cfg(any(
+    feature = "solana-program",
+    feature = "solana-program-1",
+    feature = "anchor-lang",
+    feature = "solana-pubkey"
+))]
+mod pda;
+mod pubkey;
+mod set;
+pub mod sha256;
+
+pub use base58::*;
+pub use cmp::*;
+pub use config::*;
+pub use const_pubkey::*;
+pub use curve25519::bytes_are_curve_point;
+pub use keypair::*;
+pub use list::*;
+pub use map::*;
+#[cfg(any(
+    feature = "solana-program",
+    feature = "solana-program-1",
+    feature = "anchor-lang",
+    feature = "solana-pubkey"
+))]
+pub use pda::*;
+#[cfg(all(
+    any(feature = "solana-program", feature = "solana-program-1"),
+    not(feature = "anchor-lang")
+))]
+use pubkey::solana::hash::Hash;
+pub use pubkey::Pubkey;
+pub use set::*;
+
+/// Alias of [`DecodeError`], the error returned by [`try_str_to_pubkey`].
+pub type DecodePubkeyError = DecodeError;
+
+/// Converts a `&str` to [`Pubkey`](https://docs.rs/solana-program/latest/solana_program/pubkey/struct.Pubkey.html),
+/// returning an error instead of panicking when the string is not a valid public key.
+///
+/// Since this is a `const fn`, it can be used to choose a fallback at compile time. For example:
 ///
-/// For example:
 /// ```
-/// use const_str_to_pubkey::get_base58ch_to_number_map;
-///
-/// let map = get_base58ch_to_number_map();
-/// assert!(map['1' as usize] == 0);
-/// assert!(map['2' as usize] == 1);
-/// assert!(map['A' as usize] == 9);
-/// assert!(map['B' as usize] == 10);
-/// assert!(map['a' as usize] == 33);
-/// // Invalid characters (like uppercase 'O') are mapped to 0xFF
-/// assert!(map['O' as usize] == 0xFF);
+/// use const_str_to_pubkey::{str_to_pubkey, try_str_to_pubkey, DecodePubkeyError, Pubkey};
+///
+/// const DEFAULT_ADMIN: Pubkey = str_to_pubkey("11111111111111111111111111111111");
+/// const ADMIN_PUBKEY: Pubkey = match try_str_to_pubkey("Not a public key") {
+///     Ok(pubkey) => pubkey,
+///     Err(_) => DEFAULT_ADMIN,
+/// };
+/// assert_eq!(ADMIN_PUBKEY, DEFAULT_ADMIN);
+///
+/// assert_eq!(
+///     try_str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ0rGB4Y"),
+///     Err(DecodePubkeyError::InvalidCharacter { index: 38, character: b'0' })
+/// );
 /// ```
-pub const fn get_base58ch_to_number_map() -> [u8; 128] {
-    let mut map = [0xFF; 128];
-    let mut number = 0;
-
-    let mut i = '1' as usize;
-    while i <= '9' as usize {
-        map[i] = number;
-        number += 1;
-        i += 1;
-    }
-
-    i = 'A' as usize;
-    while i <= 'Z' as usize {
-        if i != 'I' as usize && i != 'O' as usize {
-            map[i] = number;
-            number += 1;
-        }
-        i += 1;
+pub const fn try_str_to_pubkey(s: &str) -> Result<Pubkey, DecodePubkeyError> {
+    match try_decode_base58::<32>(s) {
+        Ok(bytes) => Ok(crate::pubkey::from_bytes(bytes)),
+        Err(err) => Err(err),
     }
-
-    i = 'a' as usize;
-    while i <= 'z' as usize {
-        if i != 'l' as usize {
-            map[i] = number;
-            number += 1;
-        }
-        i += 1;
-    }
-
-    map
 }
 
 /// Converts a `&'static str` to [`Pubkey`](https://docs.rs/solana-program/latest/solana_program/pubkey/struct.Pubkey.html).
@@ -96,84 +180,498 @@ pub const fn get_base58ch_to_number_map() -> [u8; 128] {
 /// use const_str_to_pubkey::str_to_pubkey;
 /// const ADMIN_PUBKEY: Pubkey = str_to_pubkey(env!("ADMIN_PUBKEY"));
 /// ```
+///
+/// # Panics
+///
+/// Panics with [`DecodePubkeyError::message`] if the string is not a valid public key (see [`try_str_to_pubkey`]).
+/// In a const context, this is a compile error.
 pub const fn str_to_pubkey(s: &'static str) -> Pubkey {
-    let s = s.as_bytes();
-    assert!(
-        s.len() <= 44,
-        "Public key string length should be no more than 44"
-    );
-    assert!(s.len() > 0, "Public key string cannot be empty");
-
-    let map = get_base58ch_to_number_map();
-    let mut bytes = [0u8; 32];
-    let mut i = 0;
-    let mut index = 0;
-
-    while i < s.len() {
-        assert!(s[i] <= 127, "Invalid Base58 character found");
-
-        let mut val = map[s[i] as usize] as usize;
-        assert!(val != 0xFF, "Invalid Base58 character found");
-
-        let mut j = 0;
-        while j < index {
-            val += (bytes[j] as usize) * 58;
-            bytes[j] = (val & 0xFF) as u8;
-            val >>= 8;
-            j += 1;
+    match try_str_to_pubkey(s) {
+        Ok(pubkey) => pubkey,
+        Err(err) => panic!("{}", err.message()),
+    }
+}
+
+/// Converts a `&str` to the 32 bytes of a public key, returning an error instead of panicking when the string is not a
+/// valid public key.
+///
+/// This does not depend on any Solana crate. See [`str_to_pubkey_bytes`].
+pub const fn try_str_to_pubkey_bytes(s: &str) -> Result<[u8; 32], DecodePubkeyError> {
+    try_decode_base58(s)
+}
+
+/// Converts a `&str` to the 32 bytes of a public key.
+///
+/// This does not depend on any Solana crate, so it is available with `default-features = false` in `no_std` crates.
+/// For example:
+///
+/// ```
+/// use const_str_to_pubkey::str_to_pubkey_bytes;
+///
+/// const ADMIN_PUBKEY: [u8; 32] = str_to_pubkey_bytes("11111111111111111111111111111112");
+/// assert_eq!(ADMIN_PUBKEY[31], 1);
+/// ```
+///
+/// # Panics
+///
+/// Panics with [`DecodePubkeyError::message`] if the string is not a valid public key (see
+/// [`try_str_to_pubkey_bytes`]). In a const context, this is a compile error.
+pub const fn str_to_pubkey_bytes(s: &str) -> [u8; 32] {
+    match try_str_to_pubkey_bytes(s) {
+        Ok(bytes) => bytes,
+        Err(err) => panic!("{}", err.message()),
+    }
+}
+
+/// The public key that [`env_pubkey!`] falls back to when the environment variable is not set, no default is given,
+/// and the calling crate has its `placeholder-pubkey` feature enabled.
+///
+/// Its Base58 form is `P1aceho1derPubkey11111111111111111111111111`, so it is easy to spot in logs.
+pub const PLACEHOLDER_PUBKEY: Pubkey = str_to_pubkey("P1aceho1derPubkey11111111111111111111111111");
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::cluster::{profile_pubkey, select_profile};
+    pub use crate::Pubkey;
+
+    /// Called by [`env_pubkey!`](crate::env_pubkey) when the environment variable is not set and no default is given.
+    ///
+    /// `placeholder` is whether the `placeholder-pubkey` feature of the crate calling the macro is enabled.
+    pub const fn missing_env_pubkey(placeholder: bool, message: &'static str) -> Pubkey {
+        if placeholder {
+            crate::PLACEHOLDER_PUBKEY
+        } else {
+            panic!("{}", message)
         }
+    }
 
-        while val > 0 {
-            bytes[index] = (val & 0xFF) as u8;
-            index += 1;
-            val >>= 8;
+    /// Called by [`assert_pubkey_ne!`](crate::assert_pubkey_ne).
+    pub const fn assert_pubkey_ne(left: &Pubkey, right: &Pubkey, expr: &'static str) {
+        if crate::pubkey_eq(left, right) {
+            let message = crate::message::ConstMessage::<512>::new()
+                .push_str("assertion `")
+                .push_str(expr)
+                .push_str("` failed\n  left: ")
+                .push_str(crate::pubkey_to_str(left).as_str())
+                .push_str("\n right: ")
+                .push_str(crate::pubkey_to_str(right).as_str());
+            panic!("{}", message.as_str());
         }
+    }
+}
+
+/// Derives a constant [`Pubkey`](https://docs.rs/solana-program/latest/solana_program/pubkey/struct.Pubkey.html)
+/// from an environment variable at compile time, with a fallback when the variable is not set.
+///
+/// Unlike `str_to_pubkey(env!("X"))`, this does not break `cargo check` or rust-analyzer when the variable is unset.
+/// The variable is read with [`option_env!`](https://doc.rust-lang.org/core/macro.option_env.html), and decoded with
+/// [`str_to_pubkey`]. When it is not set:
+///
+/// - `env_pubkey!("X", default = "...")` decodes the default instead.
+/// - `env_pubkey!("X")` evaluates to [`PLACEHOLDER_PUBKEY`] if the crate calling the macro has a `placeholder-pubkey`
+///   feature and it is enabled, and fails to compile with an error naming the variable otherwise.
+///
+/// The `placeholder-pubkey` feature is checked in the calling crate, not in this one, because Cargo unifies the
+/// features of a dependency across the whole build: if it were a feature of this crate, enabling it in a test helper
+/// would silently turn every missing variable into the placeholder in the program itself. To opt in, declare
+/// `placeholder-pubkey = []` in the `[features]` of the crate using the macro.
+///
+/// The public key is always evaluated at compile time, even when the macro is used in a function body. For example:
+///
+/// ```
+/// use const_str_to_pubkey::{env_pubkey, str_to_pubkey, Pubkey};
+///
+/// const ADMIN_PUBKEY: Pubkey = env_pubkey!(
+///     "CONST_STR_TO_PUBKEY_DOC_ADMIN",
+///     default = "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y"
+/// );
+/// assert_eq!(ADMIN_PUBKEY, str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y"));
+/// ```
+#[macro_export]
+macro_rules! env_pubkey {
+    ($name:literal $(,)?) => {{
+        // `cfg!` in the expansion is evaluated in the calling crate, which may not declare the feature
+        #[allow(unknown_lints, unexpected_cfgs)]
+        const PUBKEY: $crate::__private::Pubkey = match option_env!($name) {
+            Some(s) => $crate::str_to_pubkey(s),
+            None => $crate::__private::missing_env_pubkey(cfg!(feature = "placeholder-pubkey"), concat!(
+                "Environment variable `",
+                $name,
+                "` is not set. Set it, give a default with `env_pubkey!(\"",
+                $name,
+                "\", default = \"...\")`, or declare and enable a `placeholder-pubkey` feature in this crate"
+            )),
+        };
+        PUBKEY
+    }};
+    ($name:literal, default = $default:expr $(,)?) => {{
+        const PUBKEY: $crate::__private::Pubkey = match option_env!($name) {
+            Some(s) => $crate::str_to_pubkey(s),
+            None => $crate::str_to_pubkey($default),
+        };
+        PUBKEY
+    }};
+}
+
+/// Declares the program ID from an environment variable, like
+/// [`declare_id!`](https://docs.rs/solana-program/latest/solana_program/macro.declare_id.html) does from a string
+/// literal.
+///
+/// The ID is read with [`env_pubkey!`], so it accepts the same `default = "..."` argument and falls back the same
+/// way when the variable is not set. This lets one program be built for different clusters without code edits, e.g.,
+/// with `PROGRAM_ID=... cargo build-sbf`. The macro generates the items of both `solana_program::declare_id!` and
+/// `anchor_lang::declare_id!`:
+///
+/// - `ID` and `ID_CONST`, the program ID,
+/// - `id()` and `id_const()`, returning it,
+/// - `check_id(&Pubkey)`, returning whether a public key is the program ID,
+/// - and a `test_id` test.
+///
+/// For example:
+///
+/// ```
+/// use const_str_to_pubkey::{declare_id_from_env, str_to_pubkey};
+///
+/// declare_id_from_env!(
+///     "CONST_STR_TO_PUBKEY_DOC_PROGRAM_ID",
+///     default = "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y"
+/// );
+///
+/// assert_eq!(id(), str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y"));
+/// assert!(check_id(&ID_CONST));
+/// ```
+#[macro_export]
+macro_rules! declare_id_from_env {
+    ($name:literal $(, default = $default:expr)? $(,)?) => {
+        #[doc = concat!("The program ID, read from the `", $name, "` environment variable at compile time.")]
+        pub const ID: $crate::__private::Pubkey = $crate::env_pubkey!($name $(, default = $default)?);
 
-        i += 1;
+        /// Const version of `ID`.
+        pub const ID_CONST: $crate::__private::Pubkey = ID;
+
+        /// Returns `true` if the given public key is the program ID.
+        pub fn check_id(id: &$crate::__private::Pubkey) -> bool {
+            id == &ID
+        }
+
+        /// Returns the program ID.
+        pub const fn id() -> $crate::__private::Pubkey {
+            ID
+        }
+
+        /// Const version of `id()`.
+        pub const fn id_const() -> $crate::__private::Pubkey {
+            ID_CONST
+        }
+
+        #[cfg(test)]
+        #[test]
+        fn test_id() {
+            assert!(check_id(&id()));
+        }
+    };
+}
+
+/// Converts a `&str` to [`Hash`](https://docs.rs/solana-program/latest/solana_program/hash/struct.Hash.html),
+/// returning an error instead of panicking when the string is not a valid hash.
+///
+/// See [`try_str_to_pubkey`].
+#[cfg(all(
+    any(feature = "solana-program", feature = "solana-program-1"),
+    not(feature = "anchor-lang")
+))]
+pub const fn try_str_to_hash(s: &str) -> Result<Hash, DecodeError> {
+    match try_decode_base58(s) {
+        Ok(bytes) => Ok(Hash::new_from_array(bytes)),
+        Err(err) => Err(err),
     }
+}
 
-    i = 0;
-    while i < s.len() && s[i] == '1' as u8 {
-        bytes[index] = 0;
-        index += 1;
+/// Converts a `&'static str` to [`Hash`](https://docs.rs/solana-program/latest/solana_program/hash/struct.Hash.html),
+/// e.g., to pin the genesis hash or a blockhash of a cluster. For example:
+///
+/// ```ignore
+/// use const_str_to_pubkey::str_to_hash;
+/// const GENESIS_HASH: Hash = str_to_hash(env!("GENESIS_HASH"));
+/// ```
+///
+/// # Panics
+///
+/// Panics with [`DecodeError::message`] if the string is not a valid hash (see [`try_str_to_hash`]).
+/// In a const context, this is a compile error.
+#[cfg(all(
+    any(feature = "solana-program", feature = "solana-program-1"),
+    not(feature = "anchor-lang")
+))]
+pub const fn str_to_hash(s: &'static str) -> Hash {
+    match try_str_to_hash(s) {
+        Ok(hash) => hash,
+        Err(err) => panic!("{}", err.message()),
     }
+}
+
+/// A 64-byte ed25519 signature, decoded at compile time by [`str_to_signature`].
+///
+/// `solana_program` does not provide a signature type that can be built in const contexts, so this type only
+/// holds the bytes. Convert it with `solana_signature::Signature::from(SIGNATURE.to_bytes())` when needed.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConstSignature([u8; 64]);
 
-    i = 0;
-    while i < 16 {
-        (bytes[i], bytes[31 - i]) = (bytes[31 - i], bytes[i]);
-        i += 1;
+impl ConstSignature {
+    /// Creates a signature from its bytes.
+    pub const fn new_from_array(signature_array: [u8; 64]) -> Self {
+        Self(signature_array)
     }
 
-    Pubkey::new_from_array(bytes)
+    /// Returns the bytes of the signature.
+    pub const fn to_bytes(self) -> [u8; 64] {
+        self.0
+    }
+
+    /// Returns a reference to the bytes of the signature.
+    pub const fn as_array(&self) -> &[u8; 64] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for ConstSignature {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<ConstSignature> for [u8; 64] {
+    fn from(signature: ConstSignature) -> Self {
+        signature.0
+    }
+}
+
+impl core::fmt::Debug for ConstSignature {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "ConstSignature({:?})", &self.0[..])
+    }
+}
+
+/// Converts a `&str` to a [`ConstSignature`], returning an error instead of panicking when the string is not a
+/// valid signature.
+///
+/// See [`try_str_to_pubkey`].
+pub const fn try_str_to_signature(s: &str) -> Result<ConstSignature, DecodeError> {
+    match try_decode_base58(s) {
+        Ok(bytes) => Ok(ConstSignature(bytes)),
+        Err(err) => Err(err),
+    }
+}
+
+/// Converts a `&'static str` to a [`ConstSignature`], e.g., to compare against a known transaction signature in
+/// tests. For example:
+///
+/// ```ignore
+/// use const_str_to_pubkey::{str_to_signature, ConstSignature};
+/// const DEPLOY_SIGNATURE: ConstSignature = str_to_signature(env!("DEPLOY_SIGNATURE"));
+/// ```
+///
+/// # Panics
+///
+/// Panics with [`DecodeError::message`] if the string is not a valid signature (see [`try_str_to_signature`]).
+/// In a const context, this is a compile error.
+pub const fn str_to_signature(s: &'static str) -> ConstSignature {
+    match try_str_to_signature(s) {
+        Ok(signature) => signature,
+        Err(err) => panic!("{}", err.message()),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use solana_program::pubkey::Pubkey as SolanaPubkey;
     use std::str::FromStr;
 
-    const MAP: [u8; 128] = get_base58ch_to_number_map();
     const PUBKEY_STR: &str = "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y";
     const PUBKEY: Pubkey = str_to_pubkey(PUBKEY_STR);
 
     #[test]
-    fn test_base58ch_to_number_map() {
-        assert_eq!(MAP['1' as usize], 0);
-        assert_eq!(MAP['2' as usize], 1);
-        assert_eq!(MAP['A' as usize], 9);
-        assert_eq!(MAP['a' as usize], 33);
+    fn test_str_to_pubkey() {
+        let gt_pubkey = SolanaPubkey::from_str(PUBKEY_STR).unwrap();
+        assert_eq!(pubkey::as_bytes(&PUBKEY), gt_pubkey.as_array());
+    }
 
-        // Invalid characters are mapped to 0xFF
-        assert_eq!(MAP['0' as usize], 0xFF);
-        assert_eq!(MAP['I' as usize], 0xFF);
-        assert_eq!(MAP['O' as usize], 0xFF);
-        assert_eq!(MAP['l' as usize], 0xFF);
-        assert_eq!(MAP['+' as usize], 0xFF);
+    #[test]
+    fn test_str_to_pubkey_bytes() {
+        const BYTES: [u8; 32] = str_to_pubkey_bytes(PUBKEY_STR);
+        assert_eq!(&BYTES, pubkey::as_bytes(&PUBKEY));
+        assert_eq!(
+            try_str_to_pubkey_bytes("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4l"),
+            Err(DecodePubkeyError::InvalidCharacter {
+                index: 43,
+                character: b'l'
+            })
+        );
     }
 
     #[test]
-    fn test_str_to_pubkey() {
-        let gt_pubkey = Pubkey::from_str(PUBKEY_STR).unwrap();
-        assert_eq!(PUBKEY, gt_pubkey);
+    fn test_try_str_to_pubkey() {
+        assert_eq!(try_str_to_pubkey(PUBKEY_STR), Ok(PUBKEY));
+        assert_eq!(try_str_to_pubkey(""), Err(DecodePubkeyError::Empty));
+        assert_eq!(
+            try_str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4YY"),
+            Err(DecodePubkeyError::TooLong)
+        );
+        assert_eq!(
+            try_str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4l"),
+            Err(DecodePubkeyError::InvalidCharacter {
+                index: 43,
+                character: b'l'
+            })
+        );
+        assert_eq!(
+            try_str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGBé"),
+            Err(DecodePubkeyError::InvalidCharacter {
+                index: 42,
+                character: 0xC3
+            })
+        );
+        assert_eq!(
+            try_str_to_pubkey("zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz"),
+            Err(DecodePubkeyError::TooLarge)
+        );
+    }
+
+    #[test]
+    fn test_leading_ones() {
+        const SYSTEM_PROGRAM: Pubkey = str_to_pubkey("11111111111111111111111111111111");
+        assert_eq!(
+            pubkey::as_bytes(&SYSTEM_PROGRAM),
+            solana_program::system_program::ID.as_array()
+        );
+
+        const ONE_LEADING_ONE: Pubkey =
+            str_to_pubkey("13cpvoZKJ28f1CDBboEmfEXMVVMcSQzBhTEMtecGWQ6v");
+        assert_eq!(
+            pubkey::as_bytes(&ONE_LEADING_ONE),
+            SolanaPubkey::from_str("13cpvoZKJ28f1CDBboEmfEXMVVMcSQzBhTEMtecGWQ6v")
+                .unwrap()
+                .as_array()
+        );
+
+        // Keys with 0 to 32 leading zero bytes, followed by a mix of small and large bytes
+        for zeros in 0..=32 {
+            let mut bytes = [0u8; 32];
+            for (i, byte) in bytes.iter_mut().enumerate().skip(zeros) {
+                *byte = if i % 2 == 0 { 1 } else { 0xF0 | i as u8 };
+            }
+            let s = SolanaPubkey::new_from_array(bytes).to_string();
+            assert_eq!(Ok(pubkey::from_bytes(bytes)), try_str_to_pubkey(&s));
+        }
+
+        // Leading ones that push the decoded length past 32 bytes
+        assert_eq!(
+            try_str_to_pubkey("111111111111111111111111111111111"),
+            Err(DecodePubkeyError::TooLarge)
+        );
+        assert_eq!(
+            try_str_to_pubkey("1YEGAxog9gxiGXxo538aAQxq55XAebpFfwU72ZUxmSHm"),
+            Err(DecodePubkeyError::TooLarge)
+        );
+        assert!(SolanaPubkey::from_str("1YEGAxog9gxiGXxo538aAQxq55XAebpFfwU72ZUxmSHm").is_err());
+    }
+
+    #[test]
+    fn test_env_pubkey() {
+        assert_eq!(
+            env_pubkey!("CONST_STR_TO_PUBKEY_UNSET", default = PUBKEY_STR),
+            PUBKEY
+        );
+        assert_eq!(
+            pubkey_to_str(&PLACEHOLDER_PUBKEY).as_str(),
+            "P1aceho1derPubkey11111111111111111111111111"
+        );
+
+        assert_eq!(
+            __private::missing_env_pubkey(true, "unreachable"),
+            PLACEHOLDER_PUBKEY
+        );
+    }
+
+    mod program {
+        crate::declare_id_from_env!(
+            "CONST_STR_TO_PUBKEY_UNSET",
+            default = "BPFLoaderUpgradeab1e11111111111111111111111"
+        );
+    }
+
+    #[test]
+    fn test_declare_id_from_env() {
+        let program_id = str_to_pubkey("BPFLoaderUpgradeab1e11111111111111111111111");
+        assert_eq!(program::ID, program_id);
+        assert_eq!(program::id_const(), program_id);
+        assert!(program::check_id(&program::ID_CONST));
+        assert!(!program::check_id(&PUBKEY));
+    }
+
+    #[cfg(all(
+        any(feature = "solana-program", feature = "solana-program-1"),
+        not(feature = "anchor-lang")
+    ))]
+    #[test]
+    fn test_str_to_hash() {
+        const HASH_STR: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+        const HASH: Hash = str_to_hash(HASH_STR);
+        assert_eq!(HASH, Hash::from_str(HASH_STR).unwrap());
+        assert_eq!(
+            try_str_to_hash("5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N90"),
+            Err(DecodeError::InvalidCharacter {
+                index: 43,
+                character: b'0'
+            })
+        );
+    }
+
+    #[test]
+    fn test_str_to_signature() {
+        const SIGNATURE_STR: &str = "5VERv8NMvzbJMEkV8xnrLkEaWRtSz9CosKDYjCJjBRnbJLgp8uirBgmQpjKhoR4tjF3ZpRzrFmBV6UjKdiSZkQUW";
+        const SIGNATURE: ConstSignature = str_to_signature(SIGNATURE_STR);
+        let gt_signature = solana_signature::Signature::from_str(SIGNATURE_STR).unwrap();
+        assert_eq!(SIGNATURE.as_array(), gt_signature.as_array());
+        assert_eq!(
+            solana_signature::Signature::from(SIGNATURE.to_bytes()),
+            gt_signature
+        );
+
+        // A public key is too short to be a signature
+        assert_eq!(try_str_to_signature(PUBKEY_STR), Err(DecodeError::TooShort));
+    }
+
+    #[test]
+    fn test_decoded_length() {
+        // Every string below is rejected by `Pubkey::from_str` as well
+        for s in [
+            "2",
+            "4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofL",
+            "zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz",
+            "JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFH",
+        ] {
+            assert!(SolanaPubkey::from_str(s).is_err());
+            assert!(try_str_to_pubkey(s).is_err());
+        }
+
+        assert_eq!(try_str_to_pubkey("2"), Err(DecodePubkeyError::TooShort));
+        assert_eq!(
+            try_str_to_pubkey("4uQeVj5tqViQh7yWWGStvkEG1Zmhx6uasJtWCJziofL"),
+            Err(DecodePubkeyError::TooShort)
+        );
+        assert_eq!(
+            try_str_to_pubkey("JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFH"),
+            Err(DecodePubkeyError::TooLarge)
+        );
+
+        // The largest value that still fits in 32 bytes
+        let max = "JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFG";
+        assert_eq!(try_str_to_pubkey(max), Ok(pubkey::from_bytes([0xFF; 32])));
+        assert_eq!(
+            SolanaPubkey::from_str(max),
+            Ok(SolanaPubkey::new_from_array([0xFF; 32]))
+        );
     }
 }
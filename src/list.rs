@@ -0,0 +1,168 @@
+//! Parsing lists of public keys, e.g., allowlists configured with a single environment variable.
+
+use crate::{
+    base58::try_decode_base58_bytes,
+    bytes::subslice,
+    message::ConstMessage,
+    pubkey::{self, Pubkey},
+};
+
+const fn is_separator(byte: u8) -> bool {
+    matches!(byte, b',' | b' ' | b'\t' | b'\n' | b'\r')
+}
+
+/// Returns the byte range of the next entry at or after `start`, or `None` if there are no more entries.
+const fn next_entry(s: &[u8], start: usize) -> Option<(usize, usize)> {
+    let mut begin = start;
+    while begin < s.len() && is_separator(s[begin]) {
+        begin += 1;
+    }
+    if begin == s.len() {
+        return None;
+    }
+
+    let mut end = begin;
+    while end < s.len() && !is_separator(s[end]) {
+        end += 1;
+    }
+    Some((begin, end))
+}
+
+/// Returns the number of entries in a list of public keys separated by commas and/or whitespace.
+///
+/// Empty entries (e.g., from a trailing comma) are not counted. For example:
+///
+/// ```
+/// use const_str_to_pubkey::count_pubkeys;
+///
+/// assert_eq!(count_pubkeys("k1, k2,k3\n"), 3);
+/// assert_eq!(count_pubkeys(" , "), 0);
+/// ```
+pub const fn count_pubkeys(s: &str) -> usize {
+    let s = s.as_bytes();
+    let mut count = 0;
+    let mut start = 0;
+    while let Some((_, end)) = next_entry(s, start) {
+        count += 1;
+        start = end;
+    }
+    count
+}
+
+/// Converts a `&'static str` with `N` public keys separated by commas and/or whitespace to `[Pubkey; N]`.
+///
+/// Each entry is decoded like [`str_to_pubkey`](crate::str_to_pubkey). This is useful for allowlists configured
+/// with a single environment variable, e.g., `ORACLES="k1,k2,k3"`:
+///
+/// ```ignore
+/// use const_str_to_pubkey::str_to_pubkeys;
+/// const ORACLES: [Pubkey; 3] = str_to_pubkeys(env!("ORACLES"));
+/// ```
+///
+/// Use the [`pubkeys!`](crate::pubkeys) macro to infer `N` from the string.
+///
+/// # Panics
+///
+/// Panics if the number of entries is not `N`, or an entry is not a valid public key, with a message including the
+/// index of the entry. In a const context, this is a compile error.
+pub const fn str_to_pubkeys<const N: usize>(s: &'static str) -> [Pubkey; N] {
+    let count = count_pubkeys(s);
+    if count != N {
+        let message = ConstMessage::<128>::new()
+            .push_str("Expected ")
+            .push_usize(N)
+            .push_str(" public keys in the list, found ")
+            .push_usize(count);
+        panic!("{}", message.as_str());
+    }
+
+    let s = s.as_bytes();
+    let mut pubkeys = [pubkey::from_bytes([0; 32]); N];
+    let mut start = 0;
+    let mut i = 0;
+    while let Some((begin, end)) = next_entry(s, start) {
+        let entry = subslice(s, begin, end);
+        match try_decode_base58_bytes(entry) {
+            Ok(bytes) => pubkeys[i] = pubkey::from_bytes(bytes),
+            Err(err) => {
+                let message = ConstMessage::<128>::new()
+                    .push_str("Invalid public key at index ")
+                    .push_usize(i)
+                    .push_str(" of the list: ")
+                    .push_str(err.message());
+                panic!("{}", message.as_str());
+            }
+        }
+        start = end;
+        i += 1;
+    }
+    pubkeys
+}
+
+/// Converts a constant list of public keys separated by commas and/or whitespace to an array, inferring its length.
+///
+/// The argument must be a constant `&'static str` expression. See [`str_to_pubkeys`]. For example:
+///
+/// ```
+/// use const_str_to_pubkey::{pubkeys, str_to_pubkey, Pubkey};
+///
+/// const ORACLES: &[Pubkey] = &pubkeys!(
+///     "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y, 11111111111111111111111111111111"
+/// );
+/// assert_eq!(ORACLES.len(), 2);
+/// assert_eq!(ORACLES[1], str_to_pubkey("11111111111111111111111111111111"));
+/// ```
+#[macro_export]
+macro_rules! pubkeys {
+    ($s:expr) => {{
+        const LIST: &str = $s;
+        const PUBKEYS: [$crate::__private::Pubkey; $crate::count_pubkeys(LIST)] =
+            $crate::str_to_pubkeys(LIST);
+        PUBKEYS
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::str_to_pubkey;
+
+    const K1: &str = "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y";
+    const K2: &str = "11111111111111111111111111111111";
+    const K3: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+    #[test]
+    fn test_str_to_pubkeys() {
+        const LIST: [Pubkey; 3] = str_to_pubkeys(
+            " CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y,11111111111111111111111111111111 ,\n\tTokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA,",
+        );
+        assert_eq!(
+            LIST,
+            [str_to_pubkey(K1), str_to_pubkey(K2), str_to_pubkey(K3)]
+        );
+
+        const EMPTY: [Pubkey; 0] = str_to_pubkeys("");
+        assert!(EMPTY.is_empty());
+
+        let inferred = pubkeys!(
+            "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y 11111111111111111111111111111111"
+        );
+        assert_eq!(inferred, [str_to_pubkey(K1), str_to_pubkey(K2)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected 2 public keys in the list, found 3")]
+    fn test_count_mismatch() {
+        str_to_pubkeys::<2>(
+            "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y,11111111111111111111111111111111,TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Invalid public key at index 1 of the list: Invalid Base58 character found"
+    )]
+    fn test_invalid_entry() {
+        str_to_pubkeys::<2>("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y,0");
+    }
+}
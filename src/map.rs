@@ -0,0 +1,187 @@
+//! A map keyed by public keys, sorted at compile time.
+
+use crate::{message::ConstMessage, pubkey::Pubkey, pubkey_cmp, pubkey_to_str, str_to_pubkey};
+use core::{cmp::Ordering, mem::MaybeUninit};
+
+/// Reinterprets an array of `MaybeUninit<T>` as an array of `T`, since `ptr::read` and `transmute` of generic arrays
+/// are not usable in const contexts on older compilers.
+union ArrayInit<T: Copy, const N: usize> {
+    uninit: [MaybeUninit<T>; N],
+    init: [T; N],
+}
+
+/// A map from `N` public keys to values, sorted at compile time so that lookups are binary searches.
+///
+/// This replaces a runtime `match` on public key bytes, e.g., to map known mints to their decimals. For example:
+///
+/// ```
+/// use const_str_to_pubkey::{str_to_pubkey, ConstPubkeyMap};
+///
+/// const DECIMALS: ConstPubkeyMap<u8, 2> = ConstPubkeyMap::from_strs([
+///     ("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", 6),
+///     ("So11111111111111111111111111111111111111112", 9),
+/// ]);
+///
+/// let usdc = str_to_pubkey("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+/// assert_eq!(DECIMALS.get(&usdc), Some(&6));
+/// assert_eq!(DECIMALS.get(&str_to_pubkey("11111111111111111111111111111111")), None);
+/// ```
+///
+/// Keys may come from environment variables as well, e.g., `(env!("REWARD_MINT"), 9)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstPubkeyMap<V, const N: usize> {
+    entries: [(Pubkey, V); N],
+}
+
+impl<V: Copy, const N: usize> ConstPubkeyMap<V, N> {
+    /// Creates a map from `(key, value)` entries, sorting them by key.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are duplicate keys. In a const context, this is a compile error.
+    pub const fn new(mut entries: [(Pubkey, V); N]) -> Self {
+        // Insertion sort, like `ConstPubkeySet::new`
+        let mut i = 1;
+        while i < N {
+            let mut j = i;
+            while j > 0 {
+                match pubkey_cmp(&entries[j - 1].0, &entries[j].0) {
+                    Ordering::Less => break,
+                    Ordering::Equal => {
+                        let message = ConstMessage::<96>::new()
+                            .push_str("Duplicate key in the map: ")
+                            .push_str(pubkey_to_str(&entries[j].0).as_str());
+                        panic!("{}", message.as_str());
+                    }
+                    Ordering::Greater => {
+                        let entry = entries[j];
+                        entries[j] = entries[j - 1];
+                        entries[j - 1] = entry;
+                    }
+                }
+                j -= 1;
+            }
+            i += 1;
+        }
+        Self { entries }
+    }
+
+    /// Creates a map from `(key string, value)` entries, decoding the keys like [`str_to_pubkey`] and sorting them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a key is not a valid public key, or there are duplicate keys. In a const context, this is a compile
+    /// error.
+    pub const fn from_strs(entries: [(&'static str, V); N]) -> Self {
+        // Written entry by entry, as there is no value to fill the array with when `N == 0`. `MaybeUninit` of a `Copy`
+        // type is `Copy`, so the array can be built by value, without the `&mut` that older compilers reject in const
+        // contexts
+        let mut decoded = [MaybeUninit::<(Pubkey, V)>::uninit(); N];
+        let mut i = 0;
+        while i < N {
+            decoded[i] = MaybeUninit::new((str_to_pubkey(entries[i].0), entries[i].1));
+            i += 1;
+        }
+        // SAFETY: all `N` entries were written above, and `MaybeUninit<T>` has the same layout as `T`
+        let decoded = unsafe { ArrayInit { uninit: decoded }.init };
+        Self::new(decoded)
+    }
+}
+
+impl<V, const N: usize> ConstPubkeyMap<V, N> {
+    /// Returns the index of the entry with `key`, with a binary search.
+    const fn find(&self, key: &Pubkey) -> Option<usize> {
+        let mut low = 0;
+        let mut high = N;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match pubkey_cmp(&self.entries[mid].0, key) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Some(mid),
+            }
+        }
+        None
+    }
+
+    /// Returns the value of `key`, or `None` if the map does not contain `key`.
+    pub const fn get(&self, key: &Pubkey) -> Option<&V> {
+        match self.find(key) {
+            Some(index) => Some(&self.entries[index].1),
+            None => None,
+        }
+    }
+
+    /// Returns whether the map contains `key`.
+    pub const fn contains_key(&self, key: &Pubkey) -> bool {
+        self.find(key).is_some()
+    }
+
+    /// Returns the entries in ascending order of keys.
+    pub const fn entries(&self) -> &[(Pubkey, V)] {
+        &self.entries
+    }
+
+    /// Returns the number of entries in the map.
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns whether the map is empty.
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_const_pubkey_map() {
+        const FEE_TIERS: ConstPubkeyMap<(u16, &str), 3> = ConstPubkeyMap::from_strs([
+            ("So11111111111111111111111111111111111111112", (30, "SOL")),
+            ("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", (5, "USDC")),
+            ("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB", (5, "USDT")),
+        ]);
+        const SOL_FEE: Option<&(u16, &str)> = FEE_TIERS.get(&str_to_pubkey(
+            "So11111111111111111111111111111111111111112",
+        ));
+        assert_eq!(SOL_FEE, Some(&(30, "SOL")));
+        assert_eq!(FEE_TIERS.len(), 3);
+
+        let mut sorted = FEE_TIERS.entries().to_vec();
+        sorted.sort_by_key(|(key, _)| *key);
+        assert_eq!(FEE_TIERS.entries(), sorted);
+        for (key, value) in FEE_TIERS.entries() {
+            assert_eq!(FEE_TIERS.get(key), Some(value));
+        }
+        assert!(!FEE_TIERS.contains_key(&Pubkey::default()));
+
+        const EMPTY: ConstPubkeyMap<u8, 0> = ConstPubkeyMap::new([]);
+        assert!(EMPTY.is_empty());
+        assert_eq!(EMPTY.get(&Pubkey::default()), None);
+    }
+
+    #[test]
+    fn test_empty_from_strs() {
+        const EMPTY: ConstPubkeyMap<u8, 0> = ConstPubkeyMap::from_strs([]);
+        assert!(EMPTY.is_empty());
+        assert_eq!(EMPTY, ConstPubkeyMap::new([]));
+        assert!(!EMPTY.contains_key(&str_to_pubkey(
+            "So11111111111111111111111111111111111111112"
+        )));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Duplicate key in the map: EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+    )]
+    fn test_duplicate_key() {
+        ConstPubkeyMap::from_strs([
+            ("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", 6),
+            ("So11111111111111111111111111111111111111112", 9),
+            ("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", 6),
+        ]);
+    }
+}
@@ -0,0 +1,78 @@
+//! Building panic messages with numbers and public keys in const contexts, where `format!` is not available.
+
+use crate::bytes::subslice;
+
+/// A string of at most `CAP` bytes, built by chaining `const fn` calls. Longer content is truncated.
+pub(crate) struct ConstMessage<const CAP: usize> {
+    buf: [u8; CAP],
+    len: usize,
+}
+
+impl<const CAP: usize> ConstMessage<CAP> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            buf: [0; CAP],
+            len: 0,
+        }
+    }
+
+    pub(crate) const fn push_str(mut self, s: &str) -> Self {
+        let bytes = s.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() && self.len < CAP {
+            self.buf[self.len] = bytes[i];
+            self.len += 1;
+            i += 1;
+        }
+        self
+    }
+
+    pub(crate) const fn push_usize(mut self, n: usize) -> Self {
+        let mut digits = [0u8; 20];
+        let mut count = 0;
+        let mut rest = n;
+        loop {
+            digits[count] = b'0' + (rest % 10) as u8;
+            count += 1;
+            rest /= 10;
+            if rest == 0 {
+                break;
+            }
+        }
+        while count > 0 && self.len < CAP {
+            count -= 1;
+            self.buf[self.len] = digits[count];
+            self.len += 1;
+        }
+        self
+    }
+
+    pub(crate) const fn as_str(&self) -> &str {
+        // Truncation may split a multi-byte character, so only keep the valid prefix
+        match core::str::from_utf8(subslice(&self.buf, 0, self.len)) {
+            Ok(s) => s,
+            Err(err) => match core::str::from_utf8(subslice(&self.buf, 0, err.valid_up_to())) {
+                Ok(s) => s,
+                Err(_) => unreachable!(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_const_message() {
+        let message = ConstMessage::<32>::new()
+            .push_str("index ")
+            .push_usize(0)
+            .push_str(" and ")
+            .push_usize(18446744073709551615);
+        assert_eq!(message.as_str(), "index 0 and 18446744073709551615");
+
+        let truncated = ConstMessage::<3>::new().push_str("abé");
+        assert_eq!(truncated.as_str(), "ab");
+    }
+}
@@ -0,0 +1,419 @@
+//! Derivation of program derived addresses (PDAs) and seed-derived addresses at compile time.
+
+use crate::{
+    bytes_are_curve_point,
+    pubkey::{self, Pubkey, PubkeyError, MAX_SEEDS, MAX_SEED_LEN},
+    sha256::Sha256,
+    str_to_pubkey,
+};
+
+const PDA_MARKER: &[u8; 21] = b"ProgramDerivedAddress";
+
+/// Derives a program address from seeds and a program ID, like
+/// [`Pubkey::create_program_address`](https://docs.rs/solana-program/latest/solana_program/pubkey/struct.Pubkey.html#method.create_program_address),
+/// returning an error instead of panicking when the seeds are invalid.
+///
+/// Returns [`PubkeyError::MaxSeedLengthExceeded`] if there are more than `MAX_SEEDS` seeds or any seed is longer than
+/// `MAX_SEED_LEN` bytes, and [`PubkeyError::InvalidSeeds`] if the derived address lies on the ed25519 curve.
+pub const fn try_create_program_address(
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+) -> Result<Pubkey, PubkeyError> {
+    if seeds.len() > MAX_SEEDS {
+        return Err(PubkeyError::MaxSeedLengthExceeded);
+    }
+
+    let mut hasher = Sha256::new();
+    let mut i = 0;
+    while i < seeds.len() {
+        if seeds[i].len() > MAX_SEED_LEN {
+            return Err(PubkeyError::MaxSeedLengthExceeded);
+        }
+        hasher = hasher.update(seeds[i]);
+        i += 1;
+    }
+
+    finish_program_address(hasher, program_id)
+}
+
+/// Hashes the program ID and the PDA marker after the seeds, and checks that the address is off the curve.
+const fn finish_program_address(
+    seeds_hasher: Sha256,
+    program_id: &Pubkey,
+) -> Result<Pubkey, PubkeyError> {
+    let hash = seeds_hasher
+        .update(pubkey::as_bytes(program_id))
+        .update(PDA_MARKER)
+        .finalize();
+    if bytes_are_curve_point(&hash) {
+        return Err(PubkeyError::InvalidSeeds);
+    }
+    Ok(pubkey::from_bytes(hash))
+}
+
+/// Derives a program address from seeds and a program ID at compile time, like
+/// [`Pubkey::create_program_address`](https://docs.rs/solana-program/latest/solana_program/pubkey/struct.Pubkey.html#method.create_program_address).
+///
+/// This saves the compute units spent on hashing at runtime. For example:
+///
+/// ```ignore
+/// use const_str_to_pubkey::{create_program_address, str_to_pubkey};
+///
+/// const PROGRAM_ID: Pubkey = str_to_pubkey(env!("PROGRAM_ID"));
+/// const VAULT: Pubkey = create_program_address(&[b"vault", &[254]], &PROGRAM_ID);
+/// ```
+///
+/// # Panics
+///
+/// Panics if there are more than `MAX_SEEDS` seeds, any seed is longer than `MAX_SEED_LEN` bytes, or the derived
+/// address lies on the ed25519 curve. In a const context, this is a compile error.
+pub const fn create_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    assert!(
+        seeds.len() <= MAX_SEEDS,
+        "The number of seeds should be no more than 16"
+    );
+    let mut i = 0;
+    while i < seeds.len() {
+        assert!(
+            seeds[i].len() <= MAX_SEED_LEN,
+            "Seed length should be no more than 32"
+        );
+        i += 1;
+    }
+
+    match try_create_program_address(seeds, program_id) {
+        Ok(pubkey) => pubkey,
+        Err(_) => panic!("Provided seeds do not result in a valid address"),
+    }
+}
+
+/// Finds a valid program address and its bump seed, like
+/// [`Pubkey::try_find_program_address`](https://docs.rs/solana-program/latest/solana_program/pubkey/struct.Pubkey.html#method.try_find_program_address).
+///
+/// Bump seeds are tried from 255 down to 1, and the first one that makes the address fall off the ed25519 curve is
+/// returned. Returns `None` if the seeds are invalid (note that the bump seed takes one of the `MAX_SEEDS` seeds) or
+/// no bump seed works.
+pub const fn try_find_program_address(
+    seeds: &[&[u8]],
+    program_id: &Pubkey,
+) -> Option<(Pubkey, u8)> {
+    if seeds.len() >= MAX_SEEDS {
+        return None;
+    }
+
+    let mut seeds_hasher = Sha256::new();
+    let mut i = 0;
+    while i < seeds.len() {
+        if seeds[i].len() > MAX_SEED_LEN {
+            return None;
+        }
+        seeds_hasher = seeds_hasher.update(seeds[i]);
+        i += 1;
+    }
+
+    let mut bump_seed = u8::MAX;
+    while bump_seed > 0 {
+        if let Ok(address) = finish_program_address(seeds_hasher.update(&[bump_seed]), program_id) {
+            return Some((address, bump_seed));
+        }
+        bump_seed -= 1;
+    }
+    None
+}
+
+/// Finds a valid program address and its bump seed at compile time, like
+/// [`Pubkey::find_program_address`](https://docs.rs/solana-program/latest/solana_program/pubkey/struct.Pubkey.html#method.find_program_address).
+///
+/// This saves the compute units spent on the bump search at runtime. For example:
+///
+/// ```ignore
+/// use const_str_to_pubkey::{find_program_address, str_to_pubkey};
+///
+/// const PROGRAM_ID: Pubkey = str_to_pubkey(env!("PROGRAM_ID"));
+/// const CONFIG: (Pubkey, u8) = find_program_address(&[b"config"], &PROGRAM_ID);
+/// ```
+///
+/// # Panics
+///
+/// Panics if there are `MAX_SEEDS` or more seeds, any seed is longer than `MAX_SEED_LEN` bytes, or no bump seed
+/// works. In a const context, this is a compile error.
+pub const fn find_program_address(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+    assert!(
+        seeds.len() < MAX_SEEDS,
+        "The number of seeds should be less than 16, leaving room for the bump seed"
+    );
+    let mut i = 0;
+    while i < seeds.len() {
+        assert!(
+            seeds[i].len() <= MAX_SEED_LEN,
+            "Seed length should be no more than 32"
+        );
+        i += 1;
+    }
+
+    match try_find_program_address(seeds, program_id) {
+        Some(address_and_bump) => address_and_bump,
+        None => panic!("Unable to find a viable program address bump seed"),
+    }
+}
+
+/// Derives an address from a base public key, a seed string and an owner program ID, like
+/// [`Pubkey::create_with_seed`](https://docs.rs/solana-program/latest/solana_program/pubkey/struct.Pubkey.html#method.create_with_seed),
+/// returning an error instead of panicking when the arguments are invalid.
+///
+/// Returns [`PubkeyError::MaxSeedLengthExceeded`] if the seed is longer than `MAX_SEED_LEN` bytes, and
+/// [`PubkeyError::IllegalOwner`] if the owner ends with the PDA marker `"ProgramDerivedAddress"`.
+pub const fn try_create_with_seed(
+    base: &Pubkey,
+    seed: &str,
+    owner: &Pubkey,
+) -> Result<Pubkey, PubkeyError> {
+    if seed.len() > MAX_SEED_LEN {
+        return Err(PubkeyError::MaxSeedLengthExceeded);
+    }
+
+    let owner = pubkey::as_bytes(owner);
+    let offset = owner.len() - PDA_MARKER.len();
+    let mut i = 0;
+    while i < PDA_MARKER.len() && owner[offset + i] == PDA_MARKER[i] {
+        i += 1;
+    }
+    if i == PDA_MARKER.len() {
+        return Err(PubkeyError::IllegalOwner);
+    }
+
+    let hash = Sha256::new()
+        .update(pubkey::as_bytes(base))
+        .update(seed.as_bytes())
+        .update(owner)
+        .finalize();
+    Ok(pubkey::from_bytes(hash))
+}
+
+/// Derives an address from a base public key, a seed string and an owner program ID at compile time, like
+/// [`Pubkey::create_with_seed`](https://docs.rs/solana-program/latest/solana_program/pubkey/struct.Pubkey.html#method.create_with_seed).
+///
+/// This is how stake and nonce accounts are often derived from a base key. For example:
+///
+/// ```ignore
+/// use const_str_to_pubkey::{create_with_seed, str_to_pubkey};
+///
+/// const BASE: Pubkey = str_to_pubkey(env!("STAKE_BASE"));
+/// const STAKE_ACCOUNT: Pubkey = create_with_seed(&BASE, "stake:0", &solana_program::stake::program::ID);
+/// ```
+///
+/// # Panics
+///
+/// Panics if the seed is longer than `MAX_SEED_LEN` bytes or the owner ends with the PDA marker. In a const context,
+/// this is a compile error.
+pub const fn create_with_seed(base: &Pubkey, seed: &str, owner: &Pubkey) -> Pubkey {
+    match try_create_with_seed(base, seed, owner) {
+        Ok(pubkey) => pubkey,
+        Err(PubkeyError::MaxSeedLengthExceeded) => panic!("Seed length should be no more than 32"),
+        Err(_) => panic!("Owner should not end with the PDA marker \"ProgramDerivedAddress\""),
+    }
+}
+
+/// The program ID of the SPL Token program.
+pub const TOKEN_PROGRAM_ID: Pubkey = str_to_pubkey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// The program ID of the SPL Token-2022 program.
+pub const TOKEN_2022_PROGRAM_ID: Pubkey =
+    str_to_pubkey("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// The program ID of the SPL Associated Token Account program.
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    str_to_pubkey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Derives the associated token account address of a wallet and a mint at compile time, like
+/// `spl_associated_token_account::get_associated_token_address_with_program_id`.
+///
+/// `token_program` is the program that owns the mint, usually [`TOKEN_PROGRAM_ID`] or [`TOKEN_2022_PROGRAM_ID`]. For
+/// example:
+///
+/// ```ignore
+/// use const_str_to_pubkey::{associated_token_address, str_to_pubkey, TOKEN_PROGRAM_ID};
+///
+/// const TREASURY: Pubkey = str_to_pubkey(env!("TREASURY"));
+/// const MINT: Pubkey = str_to_pubkey(env!("MINT"));
+/// const TREASURY_ATA: Pubkey = associated_token_address(&TREASURY, &MINT, &TOKEN_PROGRAM_ID);
+/// ```
+pub const fn associated_token_address(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Pubkey {
+    find_program_address(
+        &[
+            pubkey::as_bytes(wallet),
+            pubkey::as_bytes(token_program),
+            pubkey::as_bytes(mint),
+        ],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM_ID: Pubkey = str_to_pubkey("BPFLoaderUpgradeab1e11111111111111111111111");
+
+    #[test]
+    fn test_create_program_address() {
+        const VAULT: Pubkey = create_program_address(&[b"vault", &[254]], &PROGRAM_ID);
+        assert_eq!(
+            Ok(VAULT),
+            Pubkey::create_program_address(&[b"vault", &[254]], &PROGRAM_ID)
+        );
+
+        let max_seed = &[0xAB; MAX_SEED_LEN];
+        let program_id_bytes = PROGRAM_ID.to_bytes();
+        let mut seeds: Vec<&[u8]> = vec![b"", b"short", max_seed, &program_id_bytes];
+        let bumps: Vec<[u8; 1]> = (0..=u8::MAX).map(|bump| [bump]).collect();
+        let mut invalid = 0;
+        for bump in &bumps {
+            seeds.push(bump);
+            let expected = Pubkey::create_program_address(&seeds, &PROGRAM_ID);
+            if expected.is_err() {
+                invalid += 1;
+            }
+            assert_eq!(try_create_program_address(&seeds, &PROGRAM_ID), expected);
+            seeds.pop();
+        }
+        // About half of the hashes are on the curve
+        assert!(invalid > 64 && invalid < 192);
+    }
+
+    #[test]
+    fn test_find_program_address() {
+        const CONFIG: (Pubkey, u8) = find_program_address(&[b"config"], &PROGRAM_ID);
+        assert_eq!(
+            CONFIG,
+            Pubkey::find_program_address(&[b"config"], &PROGRAM_ID)
+        );
+
+        // Pseudo-random seeds and program IDs
+        let mut state = crate::sha256::hash(b"test_find_program_address");
+        for _ in 0..200 {
+            state = crate::sha256::hash(&state);
+            let program_id = Pubkey::new_from_array(state);
+            let seed_count = (state[0] % 4) as usize;
+            let seeds: Vec<&[u8]> = (0..seed_count)
+                .map(|i| &state[i..i + (state[i + 1] % 30) as usize])
+                .collect();
+            assert_eq!(
+                try_find_program_address(&seeds, &program_id),
+                Pubkey::try_find_program_address(&seeds, &program_id)
+            );
+        }
+    }
+
+    #[test]
+    fn test_seed_limits() {
+        let exceeded_seed = &[0xAB; MAX_SEED_LEN + 1];
+        let ok_seed: &[u8] = b"ok";
+        assert_eq!(
+            try_create_program_address(&[exceeded_seed], &PROGRAM_ID),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+        assert_eq!(
+            try_create_program_address(&[ok_seed; MAX_SEEDS + 1], &PROGRAM_ID),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+        assert_ne!(
+            try_create_program_address(&[ok_seed; MAX_SEEDS], &PROGRAM_ID),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+
+        assert_eq!(
+            try_find_program_address(&[ok_seed; MAX_SEEDS], &PROGRAM_ID),
+            None
+        );
+        assert!(try_find_program_address(&[ok_seed; MAX_SEEDS - 1], &PROGRAM_ID).is_some());
+        assert_eq!(
+            try_find_program_address(&[exceeded_seed], &PROGRAM_ID),
+            None
+        );
+    }
+
+    #[test]
+    fn test_associated_token_address() {
+        use solana_program::pubkey::Pubkey as SolanaPubkey;
+        use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+
+        // The client crate always uses `solana-program` 2.x
+        fn to_solana(pubkey: &Pubkey) -> SolanaPubkey {
+            SolanaPubkey::new_from_array(*pubkey::as_bytes(pubkey))
+        }
+
+        const WALLET: Pubkey = str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y");
+        const MINT: Pubkey = str_to_pubkey("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v");
+        const ATA: Pubkey = associated_token_address(&WALLET, &MINT, &TOKEN_PROGRAM_ID);
+        const ATA_2022: Pubkey = associated_token_address(&WALLET, &MINT, &TOKEN_2022_PROGRAM_ID);
+        let (wallet, mint) = (to_solana(&WALLET), to_solana(&MINT));
+
+        assert_eq!(
+            to_solana(&ATA),
+            get_associated_token_address_with_program_id(
+                &wallet,
+                &mint,
+                &to_solana(&TOKEN_PROGRAM_ID)
+            )
+        );
+        assert_eq!(
+            to_solana(&ATA_2022),
+            get_associated_token_address_with_program_id(
+                &wallet,
+                &mint,
+                &to_solana(&TOKEN_2022_PROGRAM_ID)
+            )
+        );
+        assert_eq!(
+            to_solana(&ATA),
+            spl_associated_token_account_client::address::get_associated_token_address(
+                &wallet, &mint
+            )
+        );
+        assert_eq!(
+            to_solana(&ASSOCIATED_TOKEN_PROGRAM_ID),
+            spl_associated_token_account_client::program::ID
+        );
+    }
+
+    #[test]
+    fn test_create_with_seed() {
+        const BASE: Pubkey = str_to_pubkey("CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y");
+        const STAKE_PROGRAM_ID: Pubkey =
+            str_to_pubkey("Stake11111111111111111111111111111111111111");
+        const STAKE_ACCOUNT: Pubkey = create_with_seed(&BASE, "stake:0", &STAKE_PROGRAM_ID);
+        assert_eq!(
+            Ok(STAKE_ACCOUNT),
+            Pubkey::create_with_seed(&BASE, "stake:0", &STAKE_PROGRAM_ID)
+        );
+
+        let max_seed = "x".repeat(MAX_SEED_LEN);
+        for seed in ["", "nonce", &max_seed, "ünïcödé"] {
+            assert_eq!(
+                try_create_with_seed(&BASE, seed, &PROGRAM_ID),
+                Pubkey::create_with_seed(&BASE, seed, &PROGRAM_ID)
+            );
+        }
+
+        let exceeded_seed = "x".repeat(MAX_SEED_LEN + 1);
+        assert_eq!(
+            try_create_with_seed(&BASE, &exceeded_seed, &PROGRAM_ID),
+            Err(PubkeyError::MaxSeedLengthExceeded)
+        );
+
+        let mut owner = [0xAB; 32];
+        owner[32 - PDA_MARKER.len()..].copy_from_slice(PDA_MARKER);
+        assert_eq!(
+            try_create_with_seed(&BASE, "nonce", &Pubkey::new_from_array(owner)),
+            Err(PubkeyError::IllegalOwner)
+        );
+        owner[31] ^= 1;
+        assert!(try_create_with_seed(&BASE, "nonce", &Pubkey::new_from_array(owner)).is_ok());
+    }
+}
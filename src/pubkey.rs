@@ -0,0 +1,95 @@
+//! The public key type of the crate, chosen by the enabled features:
+//!
+//! - `solana-program` (default): `solana_program::pubkey::Pubkey` of `solana-program` 2.x.
+//! - `solana-program-1`: `solana_program::pubkey::Pubkey` of `solana-program` 1.16 to 1.18.
+//! - `anchor-lang`: `anchor_lang::prelude::Pubkey`.
+//! - `solana-pubkey`: `solana_pubkey::Pubkey`, without pulling in the rest of `solana-program`.
+//! - None of them: `[u8; 32]`.
+
+// Only one of these features may be enabled (see the `compile_error!`s in the crate root); the `not`s only keep the
+// errors down to that one when several are.
+#[cfg(all(
+    feature = "solana-program",
+    not(any(feature = "solana-program-1", feature = "anchor-lang"))
+))]
+pub(crate) use solana_program as solana;
+
+#[cfg(feature = "solana-program-1")]
+pub(crate) use solana_program_1 as solana;
+
+#[cfg(all(feature = "anchor-lang", not(feature = "solana-program-1")))]
+pub(crate) use anchor_lang::solana_program as solana;
+
+#[cfg(any(
+    feature = "solana-program",
+    feature = "solana-program-1",
+    feature = "anchor-lang"
+))]
+pub use solana::pubkey::{Pubkey, PubkeyError, MAX_SEEDS, MAX_SEED_LEN};
+
+#[cfg(all(
+    feature = "solana-pubkey",
+    not(any(
+        feature = "solana-program",
+        feature = "solana-program-1",
+        feature = "anchor-lang"
+    ))
+))]
+pub use solana_pubkey::{Pubkey, PubkeyError, MAX_SEEDS, MAX_SEED_LEN};
+
+/// The public key type returned by [`str_to_pubkey`](crate::str_to_pubkey).
+///
+/// This is `[u8; 32]` because no feature selecting a Solana crate is enabled.
+#[cfg(not(any(
+    feature = "solana-program",
+    feature = "solana-program-1",
+    feature = "anchor-lang",
+    feature = "solana-pubkey"
+)))]
+pub type Pubkey = [u8; 32];
+
+/// Creates a public key from its bytes.
+#[cfg(any(
+    feature = "solana-program",
+    feature = "solana-program-1",
+    feature = "anchor-lang",
+    feature = "solana-pubkey"
+))]
+pub(crate) const fn from_bytes(bytes: [u8; 32]) -> Pubkey {
+    Pubkey::new_from_array(bytes)
+}
+
+/// Creates a public key from its bytes.
+#[cfg(not(any(
+    feature = "solana-program",
+    feature = "solana-program-1",
+    feature = "anchor-lang",
+    feature = "solana-pubkey"
+)))]
+pub(crate) const fn from_bytes(bytes: [u8; 32]) -> Pubkey {
+    bytes
+}
+
+/// Returns the bytes of a public key.
+#[cfg(any(
+    feature = "solana-program",
+    feature = "solana-program-1",
+    feature = "anchor-lang",
+    feature = "solana-pubkey"
+))]
+pub(crate) const fn as_bytes(pubkey: &Pubkey) -> &[u8; 32] {
+    // SAFETY: `Pubkey` is `#[repr(transparent)]` over `[u8; 32]` in every supported version, including 1.x, which
+    // has no const accessor to its bytes.
+    unsafe { &*(pubkey as *const Pubkey as *const [u8; 32]) }
+}
+
+/// Returns the bytes of a public key.
+#[cfg(not(any(
+    feature = "solana-program",
+    feature = "solana-program-1",
+    feature = "anchor-lang",
+    feature = "solana-pubkey"
+)))]
+pub(crate) const fn as_bytes(pubkey: &Pubkey) -> &[u8; 32] {
+    pubkey
+}
@@ -0,0 +1,157 @@
+//! A set of public keys sorted at compile time, for allowlists checked on-chain.
+
+use crate::{
+    message::ConstMessage,
+    pubkey::{self, Pubkey},
+    pubkey_cmp, pubkey_to_str, str_to_pubkey,
+};
+use core::cmp::Ordering;
+
+/// A set of `N` public keys, sorted at compile time so that membership is checked with a binary search.
+///
+/// Checking an allowlist with [`contains`](ConstPubkeySet::contains) takes `O(log N)` comparisons instead of the
+/// `O(N)` of a linear scan, which saves compute units on-chain. For example:
+///
+/// ```
+/// use const_str_to_pubkey::{str_to_pubkey, ConstPubkeySet};
+///
+/// const ORACLES: ConstPubkeySet<3> = ConstPubkeySet::from_strs([
+///     "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y",
+///     "11111111111111111111111111111111",
+///     "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+/// ]);
+///
+/// assert!(ORACLES.contains(&str_to_pubkey("11111111111111111111111111111111")));
+/// assert!(!ORACLES.contains(&str_to_pubkey("SysvarC1ock11111111111111111111111111111111")));
+/// ```
+///
+/// Lists from a single environment variable can be used with [`pubkeys!`](crate::pubkeys):
+///
+/// ```ignore
+/// const ORACLES: ConstPubkeySet<3> = ConstPubkeySet::new(pubkeys!(env!("ORACLES")));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ConstPubkeySet<const N: usize> {
+    pubkeys: [Pubkey; N],
+}
+
+impl<const N: usize> ConstPubkeySet<N> {
+    /// Creates a set from public keys, sorting them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are duplicate public keys. In a const context, this is a compile error.
+    pub const fn new(mut pubkeys: [Pubkey; N]) -> Self {
+        // Insertion sort, which is simple enough for const eval and fast for small allowlists
+        let mut i = 1;
+        while i < N {
+            let mut j = i;
+            while j > 0 {
+                match pubkey_cmp(&pubkeys[j - 1], &pubkeys[j]) {
+                    Ordering::Less => break,
+                    Ordering::Equal => {
+                        let message = ConstMessage::<96>::new()
+                            .push_str("Duplicate public key in the set: ")
+                            .push_str(pubkey_to_str(&pubkeys[j]).as_str());
+                        panic!("{}", message.as_str());
+                    }
+                    Ordering::Greater => {
+                        let pubkey = pubkeys[j];
+                        pubkeys[j] = pubkeys[j - 1];
+                        pubkeys[j - 1] = pubkey;
+                    }
+                }
+                j -= 1;
+            }
+            i += 1;
+        }
+        Self { pubkeys }
+    }
+
+    /// Creates a set from public key strings, decoding them like [`str_to_pubkey`] and sorting them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a string is not a valid public key, or there are duplicate public keys. In a const context, this is
+    /// a compile error.
+    pub const fn from_strs(strs: [&'static str; N]) -> Self {
+        let mut pubkeys = [pubkey::from_bytes([0; 32]); N];
+        let mut i = 0;
+        while i < N {
+            pubkeys[i] = str_to_pubkey(strs[i]);
+            i += 1;
+        }
+        Self::new(pubkeys)
+    }
+
+    /// Returns whether the set contains `pubkey`, with a binary search.
+    pub const fn contains(&self, pubkey: &Pubkey) -> bool {
+        let mut low = 0;
+        let mut high = N;
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match pubkey_cmp(&self.pubkeys[mid], pubkey) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return true,
+            }
+        }
+        false
+    }
+
+    /// Returns the public keys in ascending order.
+    pub const fn as_slice(&self) -> &[Pubkey] {
+        &self.pubkeys
+    }
+
+    /// Returns the number of public keys in the set.
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns whether the set is empty.
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_const_pubkey_set() {
+        let mut state = crate::sha256::hash(b"test_const_pubkey_set");
+        let pubkeys: [Pubkey; 64] = std::array::from_fn(|_| {
+            state = crate::sha256::hash(&state);
+            pubkey::from_bytes(state)
+        });
+
+        let set = ConstPubkeySet::new(pubkeys);
+        let mut sorted = pubkeys;
+        sorted.sort();
+        assert_eq!(set.as_slice(), sorted);
+        assert_eq!(set.len(), 64);
+
+        for pubkey in &pubkeys {
+            assert!(set.contains(pubkey));
+            let mut other = *pubkey::as_bytes(pubkey);
+            other[31] ^= 1;
+            assert!(!set.contains(&pubkey::from_bytes(other)));
+        }
+
+        const EMPTY: ConstPubkeySet<0> = ConstPubkeySet::from_strs([]);
+        assert!(EMPTY.is_empty());
+        assert!(!EMPTY.contains(&Pubkey::default()));
+    }
+
+    #[test]
+    #[should_panic(expected = "Duplicate public key in the set: 11111111111111111111111111111111")]
+    fn test_duplicate_pubkey() {
+        ConstPubkeySet::from_strs([
+            "11111111111111111111111111111111",
+            "CBNbUAykYgopeby9QC9x9pvpvoRrbmf5FrPLFZ8rGB4Y",
+            "11111111111111111111111111111111",
+        ]);
+    }
+}
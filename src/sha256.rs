@@ -0,0 +1,239 @@
+//! A SHA-256 implementation that can be evaluated at compile time.
+//!
+//! This is the hash function behind program derived addresses and `Pubkey::create_with_seed`, so that they can be
+//! derived in const contexts. For example:
+//!
+//! ```
+//! use const_str_to_pubkey::sha256;
+//!
+//! const DIGEST: [u8; 32] = sha256::hashv(&[b"a", b"bc"]);
+//! assert_eq!(DIGEST, sha256::hash(b"abc"));
+//! assert_eq!(DIGEST, solana_program::hash::hash(b"abc").to_bytes());
+//! ```
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const INITIAL_STATE: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// An incremental SHA-256 hasher.
+///
+/// Since `&mut self` methods are not usable in const contexts on older compilers, every method takes the hasher by
+/// value and returns the updated hasher. For example:
+///
+/// ```
+/// use const_str_to_pubkey::sha256::Sha256;
+///
+/// const DIGEST: [u8; 32] = Sha256::new().update(b"a").update(b"bc").finalize();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buf: [u8; 64],
+    buf_len: usize,
+    total_len: u64,
+}
+
+impl Default for Sha256 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha256 {
+    /// Creates a hasher with no data.
+    pub const fn new() -> Self {
+        Self {
+            state: INITIAL_STATE,
+            buf: [0; 64],
+            buf_len: 0,
+            total_len: 0,
+        }
+    }
+
+    /// Appends `data` to the hashed data.
+    pub const fn update(mut self, data: &[u8]) -> Self {
+        self.total_len += data.len() as u64;
+
+        let mut i = 0;
+        while i < data.len() {
+            if self.buf_len == 0 && data.len() - i >= 64 {
+                self.state = compress(self.state, data, i);
+                i += 64;
+            } else {
+                self.buf[self.buf_len] = data[i];
+                self.buf_len += 1;
+                i += 1;
+                if self.buf_len == 64 {
+                    self.state = compress(self.state, &self.buf, 0);
+                    self.buf_len = 0;
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Pads the hashed data and returns the digest.
+    pub const fn finalize(mut self) -> [u8; 32] {
+        let bit_len = self.total_len.wrapping_mul(8);
+
+        self.buf[self.buf_len] = 0x80;
+        self.buf_len += 1;
+        if self.buf_len > 56 {
+            while self.buf_len < 64 {
+                self.buf[self.buf_len] = 0;
+                self.buf_len += 1;
+            }
+            self.state = compress(self.state, &self.buf, 0);
+            self.buf_len = 0;
+        }
+        while self.buf_len < 56 {
+            self.buf[self.buf_len] = 0;
+            self.buf_len += 1;
+        }
+
+        let len_bytes = bit_len.to_be_bytes();
+        let mut i = 0;
+        while i < 8 {
+            self.buf[56 + i] = len_bytes[i];
+            i += 1;
+        }
+        self.state = compress(self.state, &self.buf, 0);
+
+        let mut digest = [0u8; 32];
+        i = 0;
+        while i < 8 {
+            let word = self.state[i].to_be_bytes();
+            digest[4 * i] = word[0];
+            digest[4 * i + 1] = word[1];
+            digest[4 * i + 2] = word[2];
+            digest[4 * i + 3] = word[3];
+            i += 1;
+        }
+        digest
+    }
+}
+
+/// Processes the 64-byte block of `data` starting at `offset`.
+const fn compress(state: [u32; 8], data: &[u8], offset: usize) -> [u32; 8] {
+    let mut w = [0u32; 64];
+    let mut i = 0;
+    while i < 16 {
+        let j = offset + 4 * i;
+        w[i] = u32::from_be_bytes([data[j], data[j + 1], data[j + 2], data[j + 3]]);
+        i += 1;
+    }
+    while i < 64 {
+        let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+        let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+        w[i] = w[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(w[i - 7])
+            .wrapping_add(s1);
+        i += 1;
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    i = 0;
+    while i < 64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(K[i])
+            .wrapping_add(w[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+        i += 1;
+    }
+
+    [
+        state[0].wrapping_add(a),
+        state[1].wrapping_add(b),
+        state[2].wrapping_add(c),
+        state[3].wrapping_add(d),
+        state[4].wrapping_add(e),
+        state[5].wrapping_add(f),
+        state[6].wrapping_add(g),
+        state[7].wrapping_add(h),
+    ]
+}
+
+/// Returns the SHA-256 digest of `val`.
+pub const fn hash(val: &[u8]) -> [u8; 32] {
+    Sha256::new().update(val).finalize()
+}
+
+/// Returns the SHA-256 digest of the concatenation of `vals`, like `solana_program::hash::hashv`.
+pub const fn hashv(vals: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    let mut i = 0;
+    while i < vals.len() {
+        hasher = hasher.update(vals[i]);
+        i += 1;
+    }
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_digests() {
+        const EMPTY: [u8; 32] = hash(b"");
+        assert_eq!(
+            EMPTY,
+            [
+                0xe3, 0xb0, 0xc4, 0x42, 0x98, 0xfc, 0x1c, 0x14, 0x9a, 0xfb, 0xf4, 0xc8, 0x99, 0x6f,
+                0xb9, 0x24, 0x27, 0xae, 0x41, 0xe4, 0x64, 0x9b, 0x93, 0x4c, 0xa4, 0x95, 0x99, 0x1b,
+                0x78, 0x52, 0xb8, 0x55,
+            ]
+        );
+
+        // Two-block message from FIPS 180-2
+        assert_eq!(
+            hash(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"),
+            [
+                0x24, 0x8d, 0x6a, 0x61, 0xd2, 0x06, 0x38, 0xb8, 0xe5, 0xc0, 0x26, 0x93, 0x0c, 0x3e,
+                0x60, 0x39, 0xa3, 0x3c, 0xe4, 0x59, 0x64, 0xff, 0x21, 0x67, 0xf6, 0xec, 0xed, 0xd4,
+                0x19, 0xdb, 0x06, 0xc1,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hashv_matches_solana() {
+        let data: Vec<u8> = (0..300u32).map(|i| (i * 7 + 3) as u8).collect();
+        for len in 0..data.len() {
+            let (a, b) = data[..len].split_at(len / 3);
+            assert_eq!(
+                hashv(&[a, b]),
+                solana_program::hash::hashv(&[a, b]).to_bytes(),
+                "length {len}"
+            );
+        }
+    }
+}